@@ -0,0 +1,214 @@
+//! Parsing of `#[named_id(...)]` attributes.
+
+use quote::ToTokens;
+use syn::{
+    Attribute, Field, Fields, LitStr, Member, Path, Token, WherePredicate, meta::ParseNestedMeta,
+    punctuated::Punctuated,
+};
+
+/// Options on the type itself, e.g. `#[named_id(crate = "...", bound = "...")]`
+#[derive(Default)]
+pub struct ContainerAttrs {
+    pub bound: Option<Punctuated<WherePredicate, Token![,]>>,
+    pub krate: Option<Path>,
+    /// Only fields marked `#[named_id(include)]` are visited
+    pub only: bool,
+    /// For unions, a `fn(&Self) -> Option<&'static str>` naming the initialized field,
+    /// given as `unsafe(active = path)`
+    pub active: Option<Path>,
+}
+
+/// Options on an enum variant, e.g. `#[named_id(skip)]` or `#[named_id(name_field = "id")]`
+#[derive(Default)]
+pub struct VariantAttrs {
+    pub skip: bool,
+    /// The field whose name stands for the whole enum, by name or tuple index
+    pub name_field: Option<LitStr>,
+}
+
+/// Options on a field, e.g. `#[named_id(skip)]` or `#[named_id(with = path)]`
+#[derive(Default)]
+pub struct FieldAttrs {
+    pub skip: bool,
+    pub include: bool,
+    pub with: Option<Path>,
+}
+
+impl ContainerAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        Self::parse_keys(attrs, true)
+    }
+
+    /// Options of a type whose fields aren't visited, which are only `bound` and `crate`
+    pub fn parse_fieldless(attrs: &[Attribute]) -> syn::Result<Self> {
+        Self::parse_keys(attrs, false)
+    }
+
+    fn parse_keys(attrs: &[Attribute], visits_fields: bool) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+        for_each_meta(attrs, |meta| {
+            if meta.path.is_ident("bound") {
+                let bound: LitStr = meta.value()?.parse()?;
+                let bound = bound.parse_with(Punctuated::parse_terminated)?;
+                set_once(&meta, &mut parsed.bound, bound)
+            } else if meta.path.is_ident("crate") {
+                let krate: LitStr = meta.value()?.parse()?;
+                set_once(&meta, &mut parsed.krate, krate.parse()?)
+            } else if visits_fields && meta.path.is_ident("only") {
+                set_flag(&meta, &mut parsed.only)
+            } else if visits_fields && meta.path.is_ident("unsafe") {
+                // Reading a union field is only sound if `active` names the initialized one
+                meta.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("active") {
+                        let active: Path = meta.value()?.parse()?;
+                        set_once(&meta, &mut parsed.active, active)
+                    } else {
+                        Err(unknown(&meta, "unsafe", "`active`"))
+                    }
+                })
+            } else if visits_fields && meta.path.is_ident("active") {
+                Err(meta.error(
+                    "`active` must be written `unsafe(active = path)`, since naming the wrong field is undefined behavior",
+                ))
+            } else if visits_fields {
+                Err(unknown(
+                    &meta,
+                    "container",
+                    "`bound`, `crate`, `only` or `unsafe(active = path)`",
+                ))
+            } else {
+                Err(unknown(&meta, "container", "`bound` or `crate`"))
+            }
+        })?;
+        Ok(parsed)
+    }
+}
+
+impl VariantAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+        for_each_meta(attrs, |meta| {
+            if meta.path.is_ident("skip") {
+                set_flag(&meta, &mut parsed.skip)?;
+            } else if meta.path.is_ident("name_field") {
+                let name_field: LitStr = meta.value()?.parse()?;
+                set_once(&meta, &mut parsed.name_field, name_field)?;
+            } else {
+                return Err(unknown(&meta, "variant", "`skip` or `name_field`"));
+            }
+            if parsed.skip && parsed.name_field.is_some() {
+                return Err(meta.error("`skip` and `name_field` can't be used together"));
+            }
+            Ok(())
+        })?;
+        Ok(parsed)
+    }
+
+    /// Options of an already validated variant
+    pub fn of(attrs: &[Attribute]) -> Self {
+        Self::parse(attrs).unwrap_or_default()
+    }
+
+    /// Find the field named by `name_field`, along with its member for patterns
+    pub fn find_name_field<'a>(
+        name_field: &LitStr,
+        fields: &'a Fields,
+    ) -> syn::Result<(Member, &'a Field)> {
+        let wanted = name_field.value();
+        fields
+            .iter()
+            .enumerate()
+            .find_map(|(idx, field)| match &field.ident {
+                Some(ident) if *ident == wanted => Some((Member::Named(ident.clone()), field)),
+                None if idx.to_string() == wanted => Some((Member::Unnamed(idx.into()), field)),
+                _ => None,
+            })
+            .ok_or_else(|| syn::Error::new(name_field.span(), format!("no field `{wanted}`")))
+    }
+}
+
+impl FieldAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+        for_each_meta(attrs, |meta| {
+            if meta.path.is_ident("skip") {
+                set_flag(&meta, &mut parsed.skip)?;
+            } else if meta.path.is_ident("include") {
+                set_flag(&meta, &mut parsed.include)?;
+            } else if meta.path.is_ident("with") {
+                let with: Path = meta.value()?.parse()?;
+                set_once(&meta, &mut parsed.with, with)?;
+            } else {
+                return Err(unknown(&meta, "field", "`skip`, `include` or `with`"));
+            }
+            if parsed.skip && parsed.with.is_some() {
+                return Err(meta.error("`skip` and `with` can't be used together"));
+            }
+            if parsed.skip && parsed.include {
+                return Err(meta.error("`skip` and `include` can't be used together"));
+            }
+            Ok(())
+        })?;
+        Ok(parsed)
+    }
+
+    /// Options of an already validated field
+    pub fn of(attrs: &[Attribute]) -> Self {
+        Self::parse(attrs).unwrap_or_default()
+    }
+
+    /// Whether the field is visited, given whether the type is in `only` mode
+    pub fn is_visited(&self, only: bool) -> bool {
+        if only { self.include } else { !self.skip }
+    }
+
+    /// Whether the field is visited through its own `Rename` impl
+    pub fn uses_rename(&self, only: bool) -> bool {
+        self.is_visited(only) && self.with.is_none()
+    }
+}
+
+/// Parse every `#[named_id(...)]` attribute, reporting the errors of all of them together
+fn for_each_meta(
+    attrs: &[Attribute],
+    mut parse: impl FnMut(ParseNestedMeta) -> syn::Result<()>,
+) -> syn::Result<()> {
+    let mut errors: Option<syn::Error> = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("named_id")) {
+        if let Err(err) = attr.parse_nested_meta(&mut parse) {
+            match &mut errors {
+                Some(errors) => errors.combine(err),
+                None => errors = Some(err),
+            }
+        }
+    }
+    errors.map_or(Ok(()), Err)
+}
+
+fn set_once<T>(meta: &ParseNestedMeta, slot: &mut Option<T>, value: T) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(duplicate(meta));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn set_flag(meta: &ParseNestedMeta, flag: &mut bool) -> syn::Result<()> {
+    if *flag {
+        return Err(duplicate(meta));
+    }
+    *flag = true;
+    Ok(())
+}
+
+fn duplicate(meta: &ParseNestedMeta) -> syn::Error {
+    let key = meta.path.to_token_stream();
+    meta.error(format!("duplicate `named_id` attribute `{key}`"))
+}
+
+fn unknown(meta: &ParseNestedMeta, kind: &str, expected: &str) -> syn::Error {
+    let key = meta.path.to_token_stream();
+    meta.error(format!(
+        "unknown `named_id` {kind} attribute `{key}`, expected {expected}"
+    ))
+}
@@ -1,20 +1,275 @@
+mod attrs;
+
+use attrs::{ContainerAttrs, FieldAttrs, VariantAttrs};
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::{quote, quote_spanned};
 use std::collections::HashSet;
-use syn::{Attribute, Data, DeriveInput, Fields, Index, parse_macro_input, spanned::Spanned};
-
-/// Check if a field or variant has the `#[nameables(skip)]` attribute
-fn has_skip_attr(attrs: &[Attribute]) -> bool {
-    attrs.iter().any(|attr| {
-        if attr.path().is_ident("named_id") {
-            // Parse the tokens inside the parentheses as a path
-            if let Ok(path) = attr.parse_args::<syn::Path>() {
-                return path.is_ident("skip");
+use syn::{
+    Data, DeriveInput, Fields, Index, parse_macro_input, punctuated::Punctuated, spanned::Spanned,
+};
+
+/// Check all `#[named_id(...)]` attributes up front, so the rest of the derive can assume they're valid
+fn parse_attrs(input: &DeriveInput) -> syn::Result<ContainerAttrs> {
+    let mut errors: Vec<syn::Error> = vec![];
+    let fields: Vec<_> = match &input.data {
+        Data::Struct(data_struct) => data_struct.fields.iter().collect(),
+        Data::Enum(data_enum) => {
+            for variant in &data_enum.variants {
+                match VariantAttrs::parse(&variant.attrs) {
+                    Ok(VariantAttrs {
+                        name_field: Some(name_field),
+                        ..
+                    }) => errors
+                        .extend(VariantAttrs::find_name_field(&name_field, &variant.fields).err()),
+                    Ok(_) => {}
+                    Err(err) => errors.push(err),
+                }
+            }
+            data_enum
+                .variants
+                .iter()
+                .flat_map(|variant| &variant.fields)
+                .collect()
+        }
+        Data::Union(data_union) => data_union.fields.named.iter().collect(),
+    };
+    let container = ContainerAttrs::parse(&input.attrs);
+    let only = container.as_ref().is_ok_and(|container| container.only);
+    for field in fields {
+        match FieldAttrs::parse(&field.attrs) {
+            Ok(attrs) if attrs.include && !only => errors.push(syn::Error::new_spanned(
+                field,
+                "`#[named_id(include)]` needs `#[named_id(only)]` on the type",
+            )),
+            Ok(attrs) if attrs.skip && only => errors.push(syn::Error::new_spanned(
+                field,
+                "`#[named_id(skip)]` has no effect with `#[named_id(only)]` on the type, \
+                 which skips every field without `#[named_id(include)]`",
+            )),
+            Ok(_) => {}
+            Err(err) => errors.push(err),
+        }
+    }
+    if let Ok(container) = &container {
+        match (&input.data, &container.active) {
+            (Data::Union(_), None) => errors.push(syn::Error::new_spanned(
+                &input.ident,
+                "unions need `#[named_id(unsafe(active = path))]`, naming the initialized field",
+            )),
+            (Data::Struct(_) | Data::Enum(_), Some(active)) => errors.push(
+                syn::Error::new_spanned(active, "`active` is only for unions"),
+            ),
+            _ => {}
+        }
+    }
+    errors.extend(container.as_ref().err().cloned());
+    match errors.into_iter().reduce(|mut all, err| {
+        all.combine(err);
+        all
+    }) {
+        Some(err) => Err(err),
+        None => container,
+    }
+}
+
+/// Check the attributes of a `RenameNone` type, which can't have field or variant attributes
+fn parse_fieldless_attrs(input: &DeriveInput) -> syn::Result<ContainerAttrs> {
+    let mut errors: Vec<syn::Error> = vec![];
+    let mut reject = |attrs: &[syn::Attribute], kind: &str| {
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("named_id")) {
+            errors.push(syn::Error::new_spanned(
+                attr,
+                format!("`RenameNone` visits no fields, so it has no `named_id` {kind} attributes"),
+            ));
+        }
+    };
+    match &input.data {
+        Data::Struct(data_struct) => {
+            for field in &data_struct.fields {
+                reject(&field.attrs, "field");
+            }
+        }
+        Data::Enum(data_enum) => {
+            for variant in &data_enum.variants {
+                reject(&variant.attrs, "variant");
+                for field in &variant.fields {
+                    reject(&field.attrs, "field");
+                }
+            }
+        }
+        Data::Union(data_union) => {
+            for field in &data_union.fields.named {
+                reject(&field.attrs, "field");
+            }
+        }
+    }
+    let container = ContainerAttrs::parse_fieldless(&input.attrs);
+    errors.extend(container.as_ref().err().cloned());
+    match errors.into_iter().reduce(|mut all, err| {
+        all.combine(err);
+        all
+    }) {
+        Some(err) => Err(err),
+        None => container,
+    }
+}
+
+/// Visit the nameables of a field, given its index, an expression for it and whether that's a binding by reference
+fn visit_field(
+    field: &syn::Field,
+    idx: usize,
+    place: proc_macro2::TokenStream,
+    by_ref: bool,
+    visitor: &syn::Ident,
+    krate: &syn::Path,
+) -> proc_macro2::TokenStream {
+    let span = field.ty.span();
+    let field_ref = if by_ref {
+        place.clone()
+    } else {
+        quote! { &#place }
+    };
+    match FieldAttrs::of(&field.attrs).with {
+        Some(with) => quote_spanned! { span =>
+            for nameable in #with(#field_ref) {
+                #visitor(nameable);
+            }
+        },
+        None => {
+            // A type named after the field, for the error when the field isn't Rename
+            let marker = match &field.ident {
+                Some(ident) => ident.clone(),
+                None => syn::Ident::new(&format!("_{idx}"), Span::call_site()),
+            };
+            quote_spanned! { span =>
+                {
+                    #[allow(dead_code, non_camel_case_types)]
+                    struct #marker {}
+                    (&&#krate::derive_support::Probe::<_, #marker>::new(#field_ref))
+                        .visit_field(#visitor);
+                }
+            }
+        }
+    }
+}
+
+/// Add the inferred bounds to the type's generics, or those from `#[named_id(bound = "...")]`
+fn add_bounds(
+    input: &DeriveInput,
+    bound: Option<Punctuated<syn::WherePredicate, syn::Token![,]>>,
+    krate: &syn::Path,
+    only: bool,
+) -> syn::Generics {
+    // Collect all generic type parameter names
+    let generic_param_names: HashSet<_> = input
+        .generics
+        .params
+        .iter()
+        .filter_map(|param| {
+            if let syn::GenericParam::Type(type_param) = param {
+                Some(type_param.ident.clone())
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    // Collect generic parameters used in fields visited through their own `Rename` impl
+    let mut used_generic_params = HashSet::new();
+
+    match &input.data {
+        Data::Struct(data_struct) => match &data_struct.fields {
+            Fields::Named(fields) => {
+                for field in fields.named.iter() {
+                    if FieldAttrs::of(&field.attrs).uses_rename(only) {
+                        used_generic_params.extend(collect_generic_params_in_type(
+                            &field.ty,
+                            &generic_param_names,
+                        ));
+                    }
+                }
+            }
+            Fields::Unnamed(fields) => {
+                for field in fields.unnamed.iter() {
+                    if FieldAttrs::of(&field.attrs).uses_rename(only) {
+                        used_generic_params.extend(collect_generic_params_in_type(
+                            &field.ty,
+                            &generic_param_names,
+                        ));
+                    }
+                }
+            }
+            Fields::Unit => {}
+        },
+        Data::Enum(data_enum) => {
+            for variant in &data_enum.variants {
+                let variant_skip = VariantAttrs::of(&variant.attrs).skip;
+                if variant_skip {
+                    continue;
+                }
+
+                match &variant.fields {
+                    Fields::Named(fields) => {
+                        for field in fields.named.iter() {
+                            if FieldAttrs::of(&field.attrs).uses_rename(only) {
+                                used_generic_params.extend(collect_generic_params_in_type(
+                                    &field.ty,
+                                    &generic_param_names,
+                                ));
+                            }
+                        }
+                    }
+                    Fields::Unnamed(fields) => {
+                        for field in fields.unnamed.iter() {
+                            if FieldAttrs::of(&field.attrs).uses_rename(only) {
+                                used_generic_params.extend(collect_generic_params_in_type(
+                                    &field.ty,
+                                    &generic_param_names,
+                                ));
+                            }
+                        }
+                    }
+                    Fields::Unit => {}
+                }
             }
         }
-        false
-    })
+        Data::Union(data_union) => {
+            for field in data_union.fields.named.iter() {
+                if FieldAttrs::of(&field.attrs).uses_rename(only) {
+                    used_generic_params.extend(collect_generic_params_in_type(
+                        &field.ty,
+                        &generic_param_names,
+                    ));
+                }
+            }
+        }
+    }
+
+    // Add bounds to type parameters, unless #[named_id(bound = "...")] replaces them:
+    // - Rename bound only to type parameters used in non-skipped fields
+    // - Debug bound to all type parameters (required by Rename trait)
+    let mut generics_with_bounds = input.generics.clone();
+    if let Some(bound) = bound {
+        generics_with_bounds
+            .make_where_clause()
+            .predicates
+            .extend(bound);
+    } else {
+        for param in &mut generics_with_bounds.params {
+            if let syn::GenericParam::Type(type_param) = param {
+                // Always add Debug bound (required by Rename trait)
+                type_param.bounds.push(syn::parse_quote!(::std::fmt::Debug));
+
+                // Add Rename bound only if used in non-skipped fields
+                if used_generic_params.contains(&type_param.ident) {
+                    type_param.bounds.push(syn::parse_quote!(#krate::Rename));
+                }
+            }
+        }
+    }
+
+    generics_with_bounds
 }
 
 /// Collect all generic type parameter identifiers used in a type
@@ -38,14 +293,11 @@ fn collect_generic_params_in_type(
                     } else if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
                         // Handle nested generics like Vec<X>, Option<Y>, HashMap<K, V>, etc.
                         for arg in &args.args {
-                            match arg {
-                                syn::GenericArgument::Type(nested_ty) => {
-                                    found.extend(collect_generic_params_in_type(
-                                        nested_ty,
-                                        generic_param_names,
-                                    ));
-                                }
-                                _ => {}
+                            if let syn::GenericArgument::Type(nested_ty) = arg {
+                                found.extend(collect_generic_params_in_type(
+                                    nested_ty,
+                                    generic_param_names,
+                                ));
                             }
                         }
                     }
@@ -54,14 +306,11 @@ fn collect_generic_params_in_type(
                 // Handle paths with multiple segments like std::collections::HashMap<X, Y>
                 if let syn::PathArguments::AngleBracketed(args) = &last_segment.arguments {
                     for arg in &args.args {
-                        match arg {
-                            syn::GenericArgument::Type(nested_ty) => {
-                                found.extend(collect_generic_params_in_type(
-                                    nested_ty,
-                                    generic_param_names,
-                                ));
-                            }
-                            _ => {}
+                        if let syn::GenericArgument::Type(nested_ty) = arg {
+                            found.extend(collect_generic_params_in_type(
+                                nested_ty,
+                                generic_param_names,
+                            ));
                         }
                     }
                 }
@@ -102,356 +351,613 @@ fn collect_generic_params_in_type(
     found
 }
 
+/// Implement `Rename` for `Box<Self>`, `&Self`, `&mut Self` and `Pin<Box<Self>>`.
+/// These can't have generic impls, since they're `Nameable` when their contents are, so without
+/// these a recursive `Option<Box<Self>>` field wouldn't be `Rename`.
+fn pointer_impls(
+    input: &DeriveInput,
+    generics: &syn::Generics,
+    krate: &syn::Path,
+) -> proc_macro2::TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut ref_generics = generics.clone();
+    ref_generics
+        .params
+        .insert(0, syn::parse_quote!('__named_id_ref));
+    let (ref_impl_generics, _, _) = ref_generics.split_for_impl();
+    let visitor = syn::Ident::new("visitor", Span::mixed_site());
+    let body = quote! {
+        fn visit_nameables<'__named_id>(
+                &'__named_id self,
+                #visitor: &mut dyn ::std::ops::FnMut(#krate::AnyNameable<'__named_id>),
+            ) {
+            #krate::Rename::visit_nameables(&**self, #visitor)
+        }
+    };
+    quote! {
+        impl #impl_generics #krate::Rename for ::std::boxed::Box<#name #ty_generics> #where_clause {
+            #body
+        }
+
+        impl #impl_generics #krate::Rename
+            for ::std::pin::Pin<::std::boxed::Box<#name #ty_generics>> #where_clause
+        {
+            #body
+        }
+
+        impl #ref_impl_generics #krate::Rename for &'__named_id_ref #name #ty_generics #where_clause {
+            #body
+        }
+
+        impl #ref_impl_generics #krate::Rename for &'__named_id_ref mut #name #ty_generics #where_clause {
+            #body
+        }
+    }
+}
+
+/// Derive `Rename` by visiting every field.
+///
+/// Fields can be marked with `#[named_id(skip)]` to ignore them, or with
+/// `#[named_id(with = path)]` to collect their nameables with a
+/// `fn(&FieldTy) -> Vec<AnyNameable>` instead of their own `Rename` impl.
+/// With `#[named_id(only)]` on the type, only fields marked `#[named_id(include)]` are visited.
+///
+/// An enum with `#[named_id(name_field = "id")]` on some variants gets `Nameable` instead, so that
+/// it can be named as a unit, shortened like the chosen field. Until it's named, its fields are
+/// visited as usual. It needs its own `Display` impl.
+///
+/// Unions need `#[named_id(unsafe(active = path))]`, a `fn(&Self) -> Option<&'static str>`
+/// returning the name of the initialized field. Only that field is read, so naming the wrong one
+/// is undefined behavior, which the `unsafe` acknowledges.
+///
+/// Each type parameter gets a `Debug` bound, plus `Rename` if a visited field mentions it.
+/// Like serde's `bound`, `#[named_id(bound = "T: Rename, T::Id: Nameable")]` on the type
+/// replaces these inferred bounds with its own.
+///
+/// The generated code refers to `::named_id`, which `#[named_id(crate = "path::to::named_id")]`
+/// changes for crates that re-export it.
 #[proc_macro_derive(RenameAll, attributes(named_id))]
 pub fn derive_rename_all(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
-    let generics = &input.generics;
+    let ContainerAttrs {
+        bound,
+        krate,
+        only,
+        active,
+    } = match parse_attrs(&input) {
+        Ok(attrs) => attrs,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let krate = krate.unwrap_or_else(|| syn::parse_quote!(::named_id));
+    if let Data::Enum(data_enum) = &input.data
+        && data_enum
+            .variants
+            .iter()
+            .any(|variant| VariantAttrs::of(&variant.attrs).name_field.is_some())
+    {
+        return nameable_enum(&input, data_enum, bound, only, &krate).into();
+    }
+    // Mixed-site hygiene keeps the visitor from clashing with bindings of enum fields
+    let visitor = syn::Ident::new("visitor", Span::mixed_site());
 
     let impl_block = match &input.data {
         Data::Struct(data_struct) => {
             match &data_struct.fields {
                 Fields::Named(fields) => {
-                    // For named fields, collect all field.nameables() and chain them
-                    // Skip fields with #[named_id(skip)]
+                    // For named fields, visit each field's nameables in turn
+                    // Skip fields with #[named_id(skip)], or without #[named_id(include)] in `only` mode
                     let field_calls: Vec<_> = fields
                         .named
                         .iter()
-                        .filter(|field| !has_skip_attr(&field.attrs))
+                        .filter(|field| FieldAttrs::of(&field.attrs).is_visited(only))
                         .map(|field| {
                             let field_name = &field.ident;
-                            let span = field.ty.span();
-                            quote_spanned! { span =>
-                                self.#field_name.nameables()
-                            }
+                            visit_field(
+                                field,
+                                0,
+                                quote! { self.#field_name },
+                                false,
+                                &visitor,
+                                &krate,
+                            )
                         })
                         .collect();
 
-                    if field_calls.is_empty() {
-                        quote! {
-                            Vec::new()
-                        }
-                    } else {
-                        quote! {
-                            {
-                                let mut result = Vec::new();
-                                #(
-                                    result.extend(#field_calls);
-                                )*
-                                result
-                            }
-                        }
+                    quote! {
+                        #(#field_calls)*
                     }
                 }
                 Fields::Unnamed(fields) => {
-                    // For tuple structs, collect all field.nameables() and chain them
-                    // Skip fields with #[nameables(skip)]
+                    // For tuple structs, visit each field's nameables in turn
+                    // Skip fields with #[named_id(skip)], or without #[named_id(include)] in `only` mode
                     let field_calls: Vec<_> = fields
                         .unnamed
                         .iter()
                         .enumerate()
-                        .filter(|(_, field)| !has_skip_attr(&field.attrs))
+                        .filter(|(_, field)| FieldAttrs::of(&field.attrs).is_visited(only))
                         .map(|(idx, field)| {
                             let index = Index::from(idx);
-                            let span = field.ty.span();
-                            quote_spanned! { span =>
-                                self.#index.nameables()
-                            }
+                            visit_field(field, idx, quote! { self.#index }, false, &visitor, &krate)
                         })
                         .collect();
 
-                    if field_calls.is_empty() {
-                        quote! {
-                            Vec::new()
-                        }
-                    } else {
-                        quote! {
-                            {
-                                let mut result = Vec::new();
-                                #(
-                                    result.extend(#field_calls);
-                                )*
-                                result
-                            }
-                        }
+                    quote! {
+                        #(#field_calls)*
                     }
                 }
                 Fields::Unit => {
                     // Unit structs have no fields
-                    quote! {
-                        Vec::new()
-                    }
+                    quote! {}
                 }
             }
         }
-        Data::Enum(data_enum) => {
-            // For enums, match on each variant and call nameables() on the inner values
-            let match_arms: Vec<_> = data_enum
-                .variants
+        Data::Enum(data_enum) => visit_variants(name, data_enum, only, &visitor, &krate),
+        Data::Union(data_union) => {
+            // Only the field named by the `active` function is read
+            let active = active.expect("unions have `active`");
+            let match_arms: Vec<_> = data_union
+                .fields
+                .named
                 .iter()
-                .map(|variant| {
-                    let variant_name = &variant.ident;
-                    // If the variant itself has #[named_id(skip)], skip all its fields
-                    let variant_skip = has_skip_attr(&variant.attrs);
-
-                    match &variant.fields {
-                        Fields::Named(fields) => {
-                            if variant_skip {
-                                quote! {
-                                    #name::#variant_name { .. } => Vec::new(),
-                                }
-                            } else {
-                                // Filter out fields with #[nameables(skip)]
-                                let noskip: Vec<_> = fields
-                                    .named
-                                    .iter()
-                                    .filter(|f| !has_skip_attr(&f.attrs))
-                                    .collect();
-
-                                let field_calls: Vec<_> = noskip
-                                    .iter()
-                                    .map(|f| {
-                                        let field_name = &f.ident;
-                                        let span = f.ty.span();
-                                        quote_spanned! { span => #field_name.nameables() }
-                                    })
-                                    .collect();
-
-                                // We still need to bind all fields in the pattern, even skipped ones
-                                let all_field_names: Vec<_> =
-                                    noskip.iter().map(|f| &f.ident).collect();
-
-                                if noskip.is_empty() {
-                                    quote! {
-                                        #name::#variant_name { .. } => Vec::new(),
-                                    }
-                                } else {
-                                    let variants = if noskip.len() == fields.named.len() {
-                                        quote! { #(#all_field_names,)* }
-                                    } else {
-                                        quote! { #(#all_field_names,)* .. }
-                                    };
-                                    quote! {
-                                        #name::#variant_name { #variants } => {
-                                            let mut result = Vec::new();
-                                            #(
-                                                result.extend(#field_calls);
-                                            )*
-                                            result
-                                        },
-                                    }
-                                }
-                            }
-                        }
-                        Fields::Unnamed(fields) => {
-                            if variant_skip || fields.unnamed.is_empty() {
-                                if fields.unnamed.is_empty() {
-                                    quote! {
-                                        #name::#variant_name() => Vec::new(),
-                                    }
-                                } else {
-                                    let field_idents: Vec<_> = (0..fields.unnamed.len())
-                                        .map(|i| {
-                                            syn::Ident::new(
-                                                &format!("field_{}", i),
-                                                Span::call_site(),
-                                            )
-                                        })
-                                        .collect();
-                                    quote! {
-                                        #name::#variant_name(#(#field_idents,)*) => Vec::new(),
-                                    }
-                                }
-                            } else {
-                                // Filter out fields with #[nameables(skip)]
-                                let field_calls: Vec<_> = fields
-                                    .unnamed
-                                    .iter()
-                                    .enumerate()
-                                    .filter(|(_, field)| !has_skip_attr(&field.attrs))
-                                    .map(|(i, field)| {
-                                        let ident = syn::Ident::new(
-                                            &format!("field_{}", i),
-                                            Span::call_site(),
-                                        );
-                                        let span = field.ty.span();
-                                        quote_spanned! { span => #ident.nameables() }
-                                    })
-                                    .collect();
-
-                                // We still need to bind all fields in the pattern
-                                let all_field_idents: Vec<_> = (0..fields.unnamed.len())
-                                    .map(|i| {
-                                        syn::Ident::new(&format!("field_{}", i), Span::call_site())
-                                    })
-                                    .collect();
-
-                                if field_calls.is_empty() {
-                                    quote! {
-                                        #name::#variant_name(#(#all_field_idents,)*) => Vec::new(),
-                                    }
-                                } else {
-                                    quote! {
-                                        #name::#variant_name(#(#all_field_idents,)*) => {
-                                            let mut result = Vec::new();
-                                            #(
-                                                result.extend(#field_calls);
-                                            )*
-                                            result
-                                        },
-                                    }
-                                }
-                            }
-                        }
-                        Fields::Unit => {
-                            quote! {
-                                #name::#variant_name => Vec::new(),
-                            }
+                .filter(|field| FieldAttrs::of(&field.attrs).is_visited(only))
+                .map(|field| {
+                    let field_name = &field.ident;
+                    let field_str = field_name.as_ref().map(|ident| ident.to_string());
+                    let visit = visit_field(
+                        field,
+                        0,
+                        quote! { self.#field_name },
+                        false,
+                        &visitor,
+                        &krate,
+                    );
+                    quote! {
+                        ::std::option::Option::Some(#field_str) => {
+                            // SAFETY: `unsafe(active = path)` promises that it names the initialized field
+                            unsafe { #visit }
                         }
                     }
                 })
                 .collect();
 
             quote! {
-                match self {
+                match #active(self) {
                     #(#match_arms)*
+                    _ => {}
                 }
             }
         }
-        Data::Union(_) => {
-            return syn::Error::new_spanned(name, "Rename cannot be derived for unions")
-                .to_compile_error()
-                .into();
+    };
+
+    let generics_with_bounds = add_bounds(&input, bound, &krate, only);
+    let pointer_impls = pointer_impls(&input, &generics_with_bounds, &krate);
+
+    // Split generics for impl and where clause
+    let (impl_generics, ty_generics, where_clause) = generics_with_bounds.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics #krate::Rename for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn visit_nameables<'__named_id>(
+                &'__named_id self,
+                #visitor: &mut dyn ::std::ops::FnMut(#krate::AnyNameable<'__named_id>),
+            ) {
+                use #krate::derive_support::{Fallback as _, ViaDeref as _, ViaRename as _};
+                #impl_block
+            }
         }
+
+        #pointer_impls
     };
 
-    // Collect all generic type parameter names
-    let generic_param_names: HashSet<_> = generics
-        .params
+    TokenStream::from(expanded)
+}
+
+/// Match on each variant of an enum and visit the nameables of its fields
+fn visit_variants(
+    name: &syn::Ident,
+    data_enum: &syn::DataEnum,
+    only: bool,
+    visitor: &syn::Ident,
+    krate: &syn::Path,
+) -> proc_macro2::TokenStream {
+    // For enums, match on each variant and visit the nameables of the inner values
+    let match_arms: Vec<_> = data_enum
+        .variants
         .iter()
-        .filter_map(|param| {
-            if let syn::GenericParam::Type(type_param) = param {
-                Some(type_param.ident.clone())
-            } else {
-                None
-            }
-        })
-        .collect();
+        .map(|variant| {
+            let variant_name = &variant.ident;
+            // If the variant itself has #[named_id(skip)], skip all its fields
+            let variant_skip = VariantAttrs::of(&variant.attrs).skip;
 
-    // Collect generic parameters used in non-skipped fields
-    let mut used_generic_params = HashSet::new();
+            match &variant.fields {
+                Fields::Named(fields) => {
+                    if variant_skip {
+                        quote! {
+                            #name::#variant_name { .. } => {}
+                        }
+                    } else {
+                        // Filter out fields with #[named_id(skip)]
+                        let noskip: Vec<_> = fields
+                            .named
+                            .iter()
+                            .filter(|f| FieldAttrs::of(&f.attrs).is_visited(only))
+                            .collect();
 
-    match &input.data {
-        Data::Struct(data_struct) => match &data_struct.fields {
-            Fields::Named(fields) => {
-                for field in fields.named.iter() {
-                    if !has_skip_attr(&field.attrs) {
-                        used_generic_params.extend(collect_generic_params_in_type(
-                            &field.ty,
-                            &generic_param_names,
-                        ));
-                    }
-                }
-            }
-            Fields::Unnamed(fields) => {
-                for field in fields.unnamed.iter() {
-                    if !has_skip_attr(&field.attrs) {
-                        used_generic_params.extend(collect_generic_params_in_type(
-                            &field.ty,
-                            &generic_param_names,
-                        ));
-                    }
-                }
-            }
-            Fields::Unit => {}
-        },
-        Data::Enum(data_enum) => {
-            for variant in &data_enum.variants {
-                let variant_skip = has_skip_attr(&variant.attrs);
-                if variant_skip {
-                    continue;
-                }
+                        let field_calls: Vec<_> = noskip
+                            .iter()
+                            .map(|f| {
+                                let field_name = &f.ident;
+                                visit_field(f, 0, quote! { #field_name }, true, visitor, krate)
+                            })
+                            .collect();
 
-                match &variant.fields {
-                    Fields::Named(fields) => {
-                        for field in fields.named.iter() {
-                            if !has_skip_attr(&field.attrs) {
-                                used_generic_params.extend(collect_generic_params_in_type(
-                                    &field.ty,
-                                    &generic_param_names,
-                                ));
+                        // We still need to bind all fields in the pattern, even skipped ones
+                        let all_field_names: Vec<_> = noskip.iter().map(|f| &f.ident).collect();
+
+                        if noskip.is_empty() {
+                            quote! {
+                                #name::#variant_name { .. } => {}
+                            }
+                        } else {
+                            let variants = if noskip.len() == fields.named.len() {
+                                quote! { #(#all_field_names,)* }
+                            } else {
+                                quote! { #(#all_field_names,)* .. }
+                            };
+                            quote! {
+                                #name::#variant_name { #variants } => {
+                                    #(#field_calls)*
+                                }
                             }
                         }
                     }
-                    Fields::Unnamed(fields) => {
-                        for field in fields.unnamed.iter() {
-                            if !has_skip_attr(&field.attrs) {
-                                used_generic_params.extend(collect_generic_params_in_type(
-                                    &field.ty,
-                                    &generic_param_names,
-                                ));
+                }
+                Fields::Unnamed(fields) => {
+                    if variant_skip || fields.unnamed.is_empty() {
+                        if fields.unnamed.is_empty() {
+                            quote! {
+                                #name::#variant_name() => {}
+                            }
+                        } else {
+                            let field_idents: Vec<_> = (0..fields.unnamed.len())
+                                .map(|i| {
+                                    syn::Ident::new(&format!("field_{}", i), Span::call_site())
+                                })
+                                .collect();
+                            quote! {
+                                #name::#variant_name(#(#field_idents,)*) => {}
+                            }
+                        }
+                    } else {
+                        // Filter out fields with #[named_id(skip)]
+                        let field_calls: Vec<_> = fields
+                            .unnamed
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, field)| FieldAttrs::of(&field.attrs).is_visited(only))
+                            .map(|(i, field)| {
+                                let ident =
+                                    syn::Ident::new(&format!("field_{}", i), Span::call_site());
+                                visit_field(field, i, quote! { #ident }, true, visitor, krate)
+                            })
+                            .collect();
+
+                        // We still need to bind all fields in the pattern
+                        let all_field_idents: Vec<_> = (0..fields.unnamed.len())
+                            .map(|i| syn::Ident::new(&format!("field_{}", i), Span::call_site()))
+                            .collect();
+
+                        if field_calls.is_empty() {
+                            quote! {
+                                #name::#variant_name(#(#all_field_idents,)*) => {}
+                            }
+                        } else {
+                            quote! {
+                                #name::#variant_name(#(#all_field_idents,)*) => {
+                                    #(#field_calls)*
+                                }
                             }
                         }
                     }
-                    Fields::Unit => {}
+                }
+                Fields::Unit => {
+                    quote! {
+                        #name::#variant_name => {}
+                    }
                 }
             }
+        })
+        .collect();
+
+    quote! {
+        match self {
+            #(#match_arms)*
         }
-        Data::Union(_) => {}
     }
+}
 
-    // Add bounds to type parameters:
-    // - Rename bound only to type parameters used in non-skipped fields
-    // - Debug bound to all type parameters (required by Rename trait)
-    let mut generics_with_bounds = generics.clone();
-    for param in &mut generics_with_bounds.params {
-        if let syn::GenericParam::Type(type_param) = param {
-            // Always add Debug bound (required by Rename trait)
-            type_param.bounds.push(syn::parse_quote!(::std::fmt::Debug));
-
-            // Add Rename bound only if used in non-skipped fields
-            if used_generic_params.contains(&type_param.ident) {
-                type_param.bounds.push(syn::parse_quote!(named_id::Rename));
+/// Implement `Nameable` for an enum with `#[named_id(name_field = "...")]` variants, which then
+/// gets `Rename` from the blanket impl. Each of those variants is named like its chosen field,
+/// and other variants aren't shortened. Fields are visited as usual while the enum has no name.
+fn nameable_enum(
+    input: &DeriveInput,
+    data_enum: &syn::DataEnum,
+    bound: Option<Punctuated<syn::WherePredicate, syn::Token![,]>>,
+    only: bool,
+    krate: &syn::Path,
+) -> proc_macro2::TokenStream {
+    let name = &input.ident;
+    let field = syn::Ident::new("field", Span::mixed_site());
+    let visitor = syn::Ident::new("visitor", Span::mixed_site());
+    let mut field_types = vec![];
+    let mut patterns = vec![];
+    for variant in &data_enum.variants {
+        let Some(name_field) = VariantAttrs::of(&variant.attrs).name_field else {
+            continue;
+        };
+        let variant_name = &variant.ident;
+        let (member, name_field) = VariantAttrs::find_name_field(&name_field, &variant.fields)
+            .expect("name fields are validated");
+        field_types.push(&name_field.ty);
+        patterns.push(quote! { #name::#variant_name { #member: #field, .. } });
+    }
+    let visit_fields = visit_variants(name, data_enum, only, &visitor, krate);
+
+    // The chosen fields must be Nameable and the visited ones Rename,
+    // unless #[named_id(bound = "...")] says otherwise
+    let name_field_bounds = bound.is_none().then(|| {
+        field_types
+            .iter()
+            .map(|ty| -> syn::WherePredicate { syn::parse_quote!(#ty: #krate::Nameable) })
+            .collect::<Vec<_>>()
+    });
+    let mut generics_with_bounds = add_bounds(input, bound, krate, only);
+    generics_with_bounds
+        .make_where_clause()
+        .predicates
+        .extend(name_field_bounds.into_iter().flatten());
+    let (impl_generics, ty_generics, where_clause) = generics_with_bounds.split_for_impl();
+
+    quote! {
+        impl #impl_generics #krate::Nameable for #name #ty_generics #where_clause {
+            #[allow(unreachable_patterns)]
+            fn shortener(&self) -> ::std::option::Option<#krate::Shortener> {
+                match self {
+                    #(#patterns => #krate::Nameable::shortener(#field),)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            #[allow(unreachable_patterns)]
+            fn short(&self) -> ::std::string::String {
+                match self {
+                    #(#patterns => #krate::Nameable::short(#field),)*
+                    _ => ::std::string::ToString::to_string(self),
+                }
+            }
+
+            // Other variants aren't named on first sight, so that their fields are
+            #[allow(unreachable_patterns)]
+            fn default_name_kind(&self) -> ::std::option::Option<#krate::DefaultNameKind> {
+                match self {
+                    #(#patterns => #krate::Nameable::default_name_kind(#field),)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            #[allow(unused_variables)]
+            fn visit_self<'__named_id>(
+                &'__named_id self,
+                #visitor: &mut dyn ::std::ops::FnMut(#krate::AnyNameable<'__named_id>),
+            ) {
+                if #krate::derive_support::is_named(self) {
+                    #visitor(#krate::AnyNameable::borrowed(self));
+                    return;
+                }
+                use #krate::derive_support::{Fallback as _, ViaDeref as _, ViaRename as _};
+                #visit_fields
             }
         }
     }
+}
+
+/// Derive `Rename` for a type without any nameables.
+///
+/// The only bound is that the type itself is `Debug`, so type parameters which its `Debug` impl
+/// doesn't need (e.g. in `PhantomData`) don't need to be `Debug` either.
+/// Like `RenameAll`, the inferred bound can be replaced with `#[named_id(bound = "...")]`,
+/// and the crate path set with `#[named_id(crate = "...")]`. No other attributes apply.
+#[proc_macro_derive(RenameNone, attributes(named_id))]
+pub fn derive_no_named(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let generics = &input.generics;
+    let ContainerAttrs { bound, krate, .. } = match parse_fieldless_attrs(&input) {
+        Ok(attrs) => attrs,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let krate = krate.unwrap_or_else(|| syn::parse_quote!(::named_id));
+
+    // Only the type's own Debug impl is needed (required by Rename trait), whatever it requires
+    // of the type parameters, unless #[named_id(bound = "...")] replaces it
+    let mut generics_with_bounds = generics.clone();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let bound = bound.unwrap_or_else(|| syn::parse_quote!(#name #ty_generics: ::std::fmt::Debug));
+    generics_with_bounds
+        .make_where_clause()
+        .predicates
+        .extend(bound);
+    let pointer_impls = pointer_impls(&input, &generics_with_bounds, &krate);
 
     // Split generics for impl and where clause
     let (impl_generics, ty_generics, where_clause) = generics_with_bounds.split_for_impl();
 
     let expanded = quote! {
-        impl #impl_generics named_id::Rename for #name #ty_generics #where_clause {
-            fn nameables(&self) -> ::std::vec::Vec<named_id::AnyNameable> {
-                #impl_block
+        impl #impl_generics #krate::Rename for #name #ty_generics #where_clause {
+            fn visit_nameables<'__named_id>(
+                &'__named_id self,
+                _visitor: &mut dyn ::std::ops::FnMut(#krate::AnyNameable<'__named_id>),
+            ) {
             }
         }
+
+        #pointer_impls
     };
 
     TokenStream::from(expanded)
 }
 
-#[proc_macro_derive(RenameNone)]
-pub fn derive_no_named(input: TokenStream) -> TokenStream {
+/// Format a field for `RenamedDebug`, given an expression for it and whether that's a binding by reference
+fn debug_field(
+    field: &syn::Field,
+    place: proc_macro2::TokenStream,
+    by_ref: bool,
+    visited: bool,
+    krate: &syn::Path,
+    f: &syn::Ident,
+) -> proc_macro2::TokenStream {
+    let attrs = FieldAttrs::of(&field.attrs);
+    let span = field.ty.span();
+    let field_ref = if by_ref {
+        place.clone()
+    } else {
+        quote! { &#place }
+    };
+    if !visited {
+        return field_ref;
+    }
+    match attrs.with {
+        Some(with) => quote_spanned! { span =>
+            &::std::format_args!("{}", #krate::rename(#field_ref, &#with(#field_ref), #f.alternate()))
+        },
+        None => quote_spanned! { span => #place.renamed_ref() },
+    }
+}
+
+/// Derive a `Debug` impl which renders names, like wrapping the value in `Renamed` would.
+///
+/// Each field is formatted as if it were wrapped in `Renamed`, so the field types need `Rename`,
+/// except for fields which `RenameAll` wouldn't visit. It understands the same attributes as `RenameAll`.
+#[proc_macro_derive(RenamedDebug, attributes(named_id))]
+pub fn derive_renamed_debug(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
-    let generics = &input.generics;
+    if let Data::Union(_) = &input.data {
+        return syn::Error::new_spanned(name, "RenamedDebug cannot be derived for unions")
+            .to_compile_error()
+            .into();
+    }
+    let ContainerAttrs {
+        bound, krate, only, ..
+    } = match parse_attrs(&input) {
+        Ok(attrs) => attrs,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let krate = krate.unwrap_or_else(|| syn::parse_quote!(::named_id));
+    // Mixed-site hygiene keeps the formatter from clashing with bindings of enum fields
+    let f = syn::Ident::new("f", Span::mixed_site());
 
-    // Add Debug bound to all type parameters (required by Rename trait)
-    let mut generics_with_bounds = generics.clone();
-    for param in &mut generics_with_bounds.params {
-        if let syn::GenericParam::Type(type_param) = param {
-            // Always add Debug bound (required by Rename trait)
-            type_param.bounds.push(syn::parse_quote!(::std::fmt::Debug));
+    // Build the `debug_struct` or `debug_tuple` chain for one set of fields
+    let format_fields = |type_name: String,
+                         fields: &Fields,
+                         places: Vec<proc_macro2::TokenStream>,
+                         by_ref: bool,
+                         skip_all: bool| {
+        let exprs: Vec<_> = fields
+            .iter()
+            .zip(places)
+            .map(|(field, place)| {
+                let visited = !skip_all && FieldAttrs::of(&field.attrs).is_visited(only);
+                debug_field(field, place, by_ref, visited, &krate, &f)
+            })
+            .collect();
+        match fields {
+            Fields::Named(fields) => {
+                let field_names = fields
+                    .named
+                    .iter()
+                    .map(|field| field.ident.as_ref().map(|ident| ident.to_string()));
+                quote! {
+                    #f.debug_struct(#type_name)
+                        #(.field(#field_names, #exprs))*
+                        .finish()
+                }
+            }
+            Fields::Unnamed(_) => quote! {
+                #f.debug_tuple(#type_name)
+                    #(.field(#exprs))*
+                    .finish()
+            },
+            Fields::Unit => quote! { #f.write_str(#type_name) },
         }
-    }
+    };
 
-    // Split generics for impl and where clause
+    let body = match &input.data {
+        Data::Struct(data_struct) => {
+            let places = data_struct
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(idx, field)| match &field.ident {
+                    Some(ident) => quote! { self.#ident },
+                    None => {
+                        let index = Index::from(idx);
+                        quote! { self.#index }
+                    }
+                })
+                .collect();
+            format_fields(name.to_string(), &data_struct.fields, places, false, false)
+        }
+        Data::Enum(data_enum) => {
+            let match_arms = data_enum.variants.iter().map(|variant| {
+                let variant_name = &variant.ident;
+                let bindings: Vec<_> = variant
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, field)| match &field.ident {
+                        Some(ident) => ident.clone(),
+                        None => syn::Ident::new(&format!("field_{}", idx), Span::call_site()),
+                    })
+                    .collect();
+                let places = bindings.iter().map(|binding| quote! { #binding }).collect();
+                // Fields of skipped variants are formatted as they are
+                let skip_all = VariantAttrs::of(&variant.attrs).skip;
+                let formatted = format_fields(
+                    variant_name.to_string(),
+                    &variant.fields,
+                    places,
+                    true,
+                    skip_all,
+                );
+                let pattern = match &variant.fields {
+                    Fields::Named(_) => quote! { #name::#variant_name { #(#bindings),* } },
+                    Fields::Unnamed(_) => quote! { #name::#variant_name(#(#bindings),*) },
+                    Fields::Unit => quote! { #name::#variant_name },
+                };
+                quote! { #pattern => #formatted, }
+            });
+            quote! {
+                match self {
+                    #(#match_arms)*
+                }
+            }
+        }
+        Data::Union(_) => unreachable!("unions are rejected above"),
+    };
+
+    let generics_with_bounds = add_bounds(&input, bound, &krate, only);
     let (impl_generics, ty_generics, where_clause) = generics_with_bounds.split_for_impl();
 
     let expanded = quote! {
-        impl #impl_generics named_id::Rename for #name #ty_generics #where_clause {
-            fn nameables(&self) -> ::std::vec::Vec<named_id::AnyNameable> {
-                ::std::vec::Vec::new()
+        impl #impl_generics ::std::fmt::Debug for #name #ty_generics #where_clause {
+            fn fmt(&self, #f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                // Fields are renamed with method calls, so that auto-deref applies
+                use #krate::Rename as _;
+                #body
             }
         }
     };
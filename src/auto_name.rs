@@ -0,0 +1,47 @@
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+static DEFAULT_NAME_KIND: AtomicU8 = AtomicU8::new(0);
+
+thread_local! {
+    static RENDERING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The kind of name given to IDs which are rendered before being named explicitly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum DefaultNameKind {
+    /// As if [`with_serial`](crate::Nameable::with_serial) was called, e.g. `⟪ID|#007⟫`.
+    Serial = 1,
+    /// As if [`with_short`](crate::Nameable::with_short) was called, e.g. `⟪ID|1234⟫`.
+    Short = 2,
+}
+
+/// The kind of name given to unnamed IDs when they're first rendered, if any.
+pub fn default_name_kind() -> Option<DefaultNameKind> {
+    match DEFAULT_NAME_KIND.load(Ordering::Relaxed) {
+        1 => Some(DefaultNameKind::Serial),
+        2 => Some(DefaultNameKind::Short),
+        _ => None,
+    }
+}
+
+/// Name unnamed IDs automatically when they're first rendered, or stop doing so with `None`.
+/// Types can override this with [`Nameable::default_name_kind`](crate::Nameable::default_name_kind).
+/// Off by default, so unnamed IDs appear in full.
+pub fn set_default_name_kind(kind: Option<DefaultNameKind>) {
+    DEFAULT_NAME_KIND.store(kind.map_or(0, |kind| kind as u8), Ordering::Relaxed);
+}
+
+/// Run `f` with auto-naming enabled for the nameables it visits on this thread.
+/// Only rendering names things, so that merely collecting nameables has no side effects.
+/// The flag is restored even if `f` panics, so that a caught panic can't leave it on.
+pub(crate) fn rendering<R>(f: impl FnOnce() -> R) -> R {
+    crate::scoped(&RENDERING, true, f)
+}
+
+pub(crate) fn is_rendering() -> bool {
+    RENDERING.get()
+}
@@ -0,0 +1,62 @@
+use std::sync::{
+    LazyLock,
+    atomic::{AtomicU8, Ordering},
+};
+
+/// When this environment variable is set to anything but an empty string, names are never colored.
+/// See <https://no-color.org>.
+pub const NO_COLOR_ENV_VAR: &str = "NO_COLOR";
+
+static COLORING: AtomicU8 = AtomicU8::new(Coloring::Off as u8);
+
+static NO_COLOR: LazyLock<bool> = LazyLock::new(|| {
+    std::env::var_os(NO_COLOR_ENV_VAR)
+        .map(|v| !v.is_empty())
+        .unwrap_or(false)
+});
+
+/// ANSI foreground colors used for names, chosen to be readable on both light and dark terminals.
+const PALETTE: [u8; 12] = [31, 32, 33, 34, 35, 36, 91, 92, 93, 94, 95, 96];
+
+/// Whether and how [`Renamed`](crate::Renamed) colors the names it renders.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Coloring {
+    /// No escape codes are written.
+    #[default]
+    Off,
+    /// Every name with the same prefix gets the same color, e.g. all `ID|` names are magenta.
+    ByPrefix,
+    /// Every distinct ID gets its own color.
+    ById,
+}
+
+/// The current coloring, which is always [`Coloring::Off`] if [`NO_COLOR_ENV_VAR`] is set.
+pub fn coloring() -> Coloring {
+    if *NO_COLOR {
+        return Coloring::Off;
+    }
+    match COLORING.load(Ordering::Relaxed) {
+        1 => Coloring::ByPrefix,
+        2 => Coloring::ById,
+        _ => Coloring::Off,
+    }
+}
+
+/// Opt in to colored names for the whole process.
+pub fn set_coloring(coloring: Coloring) {
+    COLORING.store(coloring as u8, Ordering::Relaxed);
+}
+
+/// Wrap `s` in the escape codes for the color derived from `key`.
+pub(crate) fn paint(s: &str, key: &str) -> String {
+    let code = PALETTE[(fnv1a(key.as_bytes()) % PALETTE.len() as u64) as usize];
+    format!("\x1b[{code}m{s}\x1b[0m")
+}
+
+/// A tiny hash which, unlike `DefaultHasher`, gives the same colors from one run to the next.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
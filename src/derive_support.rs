@@ -0,0 +1,72 @@
+//! Items used by the code which `#[derive(RenameAll)]` generates. Not a public API.
+//!
+//! Fields are visited through [`Probe`], which picks the first of these that applies:
+//! - the field is [`Rename`]
+//! - the field derefs to something [`Rename`], e.g. `Box<Vec<Id>>` or `ManuallyDrop<Id>`
+//! - neither, which fails with an error naming the field
+
+use std::{fmt::Debug, marker::PhantomData, ops::Deref};
+
+use crate::{AnyNameable, Rename};
+
+/// Whether an ID has a registered name, for enums with `#[named_id(name_field = "...")]`.
+pub fn is_named(id: &dyn Debug) -> bool {
+    crate::nameable::is_named(id)
+}
+
+/// A reference to the field of type `T` named `F`, where `F` is a type named after the field.
+pub struct Probe<'a, T: ?Sized, F>(&'a T, PhantomData<F>);
+
+impl<'a, T: ?Sized, F> Probe<'a, T, F> {
+    pub fn new(field: &'a T) -> Self {
+        Probe(field, PhantomData)
+    }
+}
+
+pub trait ViaRename<'a> {
+    fn visit_field(&self, visitor: &mut dyn FnMut(AnyNameable<'a>));
+}
+
+impl<'a, T: Rename + ?Sized, F> ViaRename<'a> for &&Probe<'a, T, F> {
+    fn visit_field(&self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        self.0.visit_nameables(visitor)
+    }
+}
+
+pub trait ViaDeref<'a> {
+    fn visit_field(&self, visitor: &mut dyn FnMut(AnyNameable<'a>));
+}
+
+impl<'a, T, F> ViaDeref<'a> for &Probe<'a, T, F>
+where
+    T: Deref + ?Sized,
+    T::Target: Rename,
+{
+    fn visit_field(&self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        self.0.deref().visit_nameables(visitor)
+    }
+}
+
+#[diagnostic::on_unimplemented(
+    message = "field `{F}` of type `{Self}` doesn't implement `Rename`",
+    label = "`{Self}` doesn't implement `Rename`",
+    note = "add `#[named_id(skip)]` to the field, or implement `Rename` (or `Nameable`) for `{Self}`"
+)]
+pub trait FieldRename<F>: Rename {}
+
+impl<T: Rename + ?Sized, F> FieldRename<F> for T {}
+
+pub trait Fallback<'a, T: ?Sized, F> {
+    fn visit_field(&self, visitor: &mut dyn FnMut(AnyNameable<'a>))
+    where
+        T: FieldRename<F>;
+}
+
+impl<'a, T: ?Sized, F> Fallback<'a, T, F> for Probe<'a, T, F> {
+    fn visit_field(&self, visitor: &mut dyn FnMut(AnyNameable<'a>))
+    where
+        T: FieldRename<F>,
+    {
+        self.0.visit_nameables(visitor)
+    }
+}
@@ -0,0 +1,170 @@
+//! Ready-made ID types, for when an ID is just a hash, a key or a UUID.
+//!
+//! Each is parameterized by an [`IdSpec`] which says how it's shortened, so declaring a new
+//! ID type takes one line for the spec and one for the type:
+//!
+//! ```
+//! use named_id::*;
+//!
+//! id_spec!(pub Agent, "AG");
+//! pub type AgentId = HexId<32, Agent>;
+//!
+//! let id = AgentId::new([0xab; 32]).with_short();
+//! assert_eq!(id.renamed().to_string(), "⟪AG|abab⟫");
+//! ```
+
+use std::{
+    fmt::{Debug, Display},
+    marker::PhantomData,
+};
+
+use crate::{Nameable, Shortener};
+
+/// How the IDs of one type are shortened. Usually declared with [`id_spec!`](crate::id_spec).
+pub trait IdSpec: 'static {
+    /// The prefix of every name, e.g. `"AG"` in `⟪AG|abcd⟫`.
+    const PREFIX: &'static str;
+    /// How many characters of the encoded ID are kept when shortening.
+    const LENGTH: usize = 4;
+}
+
+/// Declare a unit struct implementing [`IdSpec`], with an optional short length.
+#[macro_export]
+macro_rules! id_spec {
+    ($vis:vis $name:ident, $prefix:expr) => {
+        $crate::id_spec!($vis $name, $prefix, 4);
+    };
+    ($vis:vis $name:ident, $prefix:expr, $length:expr) => {
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        $vis struct $name;
+
+        impl $crate::IdSpec for $name {
+            const PREFIX: &'static str = $prefix;
+            const LENGTH: usize = $length;
+        }
+    };
+}
+
+/// A fixed-size byte ID, displayed as lowercase hex.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HexId<const N: usize, S>([u8; N], PhantomData<S>);
+
+/// A fixed-size byte ID, displayed as unpadded URL-safe base64.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Base64Id<const N: usize, S>([u8; N], PhantomData<S>);
+
+macro_rules! bytes_id {
+    ($ty:ident, $encode:ident) => {
+        impl<const N: usize, S> $ty<N, S> {
+            pub const fn new(bytes: [u8; N]) -> Self {
+                Self(bytes, PhantomData)
+            }
+
+            pub fn as_bytes(&self) -> &[u8; N] {
+                &self.0
+            }
+        }
+
+        impl<const N: usize, S> From<[u8; N]> for $ty<N, S> {
+            fn from(bytes: [u8; N]) -> Self {
+                Self::new(bytes)
+            }
+        }
+
+        impl<const N: usize, S: IdSpec> Display for $ty<N, S> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(&$encode(&self.0))
+            }
+        }
+
+        /// The prefix is included so that equal bytes of different ID types get different names.
+        impl<const N: usize, S: IdSpec> Debug for $ty<N, S> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}({})", S::PREFIX, self)
+            }
+        }
+
+        impl<const N: usize, S: IdSpec> Nameable for $ty<N, S> {
+            fn shortener(&self) -> Option<Shortener> {
+                Some(Shortener {
+                    length: S::LENGTH,
+                    prefix: S::PREFIX,
+                })
+            }
+        }
+    };
+}
+
+bytes_id!(HexId, hex);
+bytes_id!(Base64Id, base64);
+
+/// A UUID, shortened to its first [`IdSpec::LENGTH`] hex digits.
+#[cfg(feature = "uuid")]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UuidId<S>(uuid::Uuid, PhantomData<S>);
+
+#[cfg(feature = "uuid")]
+impl<S> UuidId<S> {
+    pub const fn new(uuid: uuid::Uuid) -> Self {
+        Self(uuid, PhantomData)
+    }
+
+    pub fn uuid(&self) -> &uuid::Uuid {
+        &self.0
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl<S> From<uuid::Uuid> for UuidId<S> {
+    fn from(uuid: uuid::Uuid) -> Self {
+        Self::new(uuid)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl<S: IdSpec> Display for UuidId<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl<S: IdSpec> Debug for UuidId<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", S::PREFIX, self.0)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl<S: IdSpec> Nameable for UuidId<S> {
+    fn shortener(&self) -> Option<Shortener> {
+        Some(Shortener {
+            length: S::LENGTH,
+            prefix: S::PREFIX,
+        })
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    bytes
+        .iter()
+        .flat_map(|b| [DIGITS[(b >> 4) as usize], DIGITS[(b & 0xf) as usize]])
+        .map(char::from)
+        .collect()
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(DIGITS[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+    out
+}
@@ -9,8 +9,49 @@ pub use nameable::*;
 mod nameables;
 pub use nameables::*;
 
-pub use named_id_derive::{RenameAll, RenameNone};
+mod mode;
+pub use mode::*;
+
+mod color;
+pub use color::*;
+
+mod template;
+pub use template::*;
+
+mod style;
+pub use style::*;
+
+mod auto_name;
+pub use auto_name::*;
+
+mod ids;
+pub use ids::*;
+
+pub mod serde_names;
+
+#[doc(hidden)]
+pub mod derive_support;
+
+pub use named_id_derive::{RenameAll, RenameNone, RenamedDebug};
 
 pub(crate) fn bracketed(s: &str, (b1, b2): (&'static str, &'static str)) -> String {
     format!("{b1}{s}{b2}")
 }
+
+/// Run `f` with a thread-local flag set to `value`, restoring it afterwards even if `f` panics.
+pub(crate) fn scoped<R>(
+    flag: &'static std::thread::LocalKey<std::cell::Cell<bool>>,
+    value: bool,
+    f: impl FnOnce() -> R,
+) -> R {
+    struct Restore(&'static std::thread::LocalKey<std::cell::Cell<bool>>, bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            self.0.set(self.1);
+        }
+    }
+
+    let _restore = Restore(flag, flag.replace(value));
+    f()
+}
@@ -0,0 +1,103 @@
+use std::{
+    fmt::Display,
+    str::FromStr,
+    sync::{
+        LazyLock,
+        atomic::{AtomicU8, Ordering},
+    },
+};
+
+/// Environment variable used to choose the initial [`Mode`], e.g. `NAMED_ID_MODE=name+short`.
+pub const MODE_ENV_VAR: &str = "NAMED_ID_MODE";
+
+static MODE: LazyLock<AtomicU8> = LazyLock::new(|| AtomicU8::new(mode_from_env() as u8));
+
+/// How [`Renamed`](crate::Renamed) renders the IDs it knows about.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Mode {
+    /// Plain `Debug` output. Nameables aren't even collected.
+    Off,
+    /// Only the short form of each ID, e.g. `⟪ID|1234⟫`, even if it has a name.
+    Short,
+    /// The name each ID was registered with, e.g. `⟪ID|foo⟫`.
+    #[default]
+    Name,
+    /// The name followed by the short form, e.g. `⟪ID|foo|1234⟫`.
+    NameShort,
+    /// The name followed by the original `Debug` output, e.g. `⟪ID|foo⟫ (Num(1234567890))`.
+    FullName,
+}
+
+impl Mode {
+    const ALL: [Mode; 5] = [
+        Mode::Off,
+        Mode::Short,
+        Mode::Name,
+        Mode::NameShort,
+        Mode::FullName,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Mode::Off => "off",
+            Mode::Short => "short",
+            Mode::Name => "name",
+            Mode::NameShort => "name+short",
+            Mode::FullName => "full+name",
+        }
+    }
+}
+
+impl Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Mode {
+    type Err = ParseModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        Mode::ALL
+            .into_iter()
+            .find(|mode| mode.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseModeError(s.to_string()))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseModeError(String);
+
+impl Display for ParseModeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid mode {:?}, expected one of: off, short, name, name+short, full+name",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseModeError {}
+
+/// The current display mode, initially taken from [`MODE_ENV_VAR`].
+pub fn mode() -> Mode {
+    Mode::ALL[MODE.load(Ordering::Relaxed) as usize]
+}
+
+/// Change the display mode for the whole process.
+pub fn set_mode(mode: Mode) {
+    MODE.store(mode as u8, Ordering::Relaxed);
+}
+
+fn mode_from_env() -> Mode {
+    match std::env::var(MODE_ENV_VAR) {
+        Ok(var) => var.parse().unwrap_or_else(|err| {
+            tracing::warn!(%err, "ignoring {MODE_ENV_VAR}");
+            Mode::default()
+        }),
+        Err(_) => Mode::default(),
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    cell::Cell,
     collections::HashMap,
     fmt::{Debug, Display},
     sync::{LazyLock, Mutex, atomic::AtomicUsize},
@@ -17,26 +18,61 @@ static NAMES: LazyLock<Mutex<HashMap<String, Name>>> = LazyLock::new(|| Mutex::n
 
 static SERIAL: AtomicUsize = AtomicUsize::new(0);
 
+thread_local! {
+    /// Whether shortening checks for collisions, which it doesn't for short forms kept in reserve
+    static CHECK_SHORT: Cell<bool> = const { Cell::new(true) };
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Name {
     prefix: Option<&'static str>,
     kind: NameKind,
+    short: Option<String>,
     brackets: (&'static str, &'static str),
+    template: Option<Template>,
 }
 
-impl std::fmt::Display for Name {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut inner = match &self.kind {
-            NameKind::Serial(serial) => format!("#{:03}", serial),
-            NameKind::Short(short) => format!("{}", short),
-            NameKind::Name(name) => format!("{}", name),
-            NameKind::NameShort { name, short } => format!("{}|{}", name, short),
+impl Name {
+    /// Render this name as it should appear in the given [`Mode`].
+    /// `full` is the original `Debug` output of the ID, used by [`Mode::FullName`] and [`Coloring::ById`].
+    pub(crate) fn render(&self, mode: Mode, coloring: Coloring, full: &str) -> String {
+        let template = self.template.unwrap_or_else(crate::template);
+        let style = style();
+        let sep = style.separator;
+        let label = match &self.kind {
+            NameKind::Serial(serial) => template.serial(*serial),
+            NameKind::Short(short) => short.clone(),
+            NameKind::Name(name) => name.clone(),
+            NameKind::NameShort { name, short } => template.name_short(sep, name, short),
+        };
+        let mut inner = match (&self.kind, mode, &self.short) {
+            (NameKind::Short(_), _, _) => label,
+            (NameKind::NameShort { short, .. }, Mode::Short, _) => short.clone(),
+            (NameKind::NameShort { .. }, _, _) => label,
+            (_, Mode::Short, Some(short)) => short.clone(),
+            (_, Mode::NameShort, Some(short)) => template.name_short(sep, &label, short),
+            _ => label,
         };
 
         if let Some(prefix) = self.prefix {
-            inner = format!("{}|{}", prefix, inner);
+            inner = template.prefixed(sep, prefix, &inner);
+        }
+        let mut name = bracketed(&inner, style.brackets(self.brackets));
+        match coloring {
+            Coloring::Off => {}
+            Coloring::ByPrefix => name = paint(&name, self.prefix.unwrap_or_default()),
+            Coloring::ById => name = paint(&name, full),
         }
-        write!(f, "{}", bracketed(&inner, self.brackets))
+        match mode {
+            Mode::FullName => format!("{name} ({full})"),
+            _ => name,
+        }
+    }
+}
+
+impl std::fmt::Display for Name {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render(Mode::Name, Coloring::Off, ""))
     }
 }
 
@@ -56,7 +92,9 @@ pub trait Nameable: Debug + Display {
         let name = Name {
             prefix: self.shortener().map(|s| s.prefix),
             kind: NameKind::Name(name.to_string()),
+            short: spare_short(&self),
             brackets: self.brackets(),
+            template: self.template(),
         };
         set_name(&self, name);
         self
@@ -72,15 +110,19 @@ pub trait Nameable: Debug + Display {
                 prefix: Some(shortener.prefix),
                 kind: NameKind::NameShort {
                     name: name.to_string(),
-                    short,
+                    short: short.clone(),
                 },
+                short: Some(short),
                 brackets: self.brackets(),
+                template: self.template(),
             }
         } else {
             Name {
                 prefix: None,
                 kind: NameKind::Name(name.to_string()),
+                short: None,
                 brackets: self.brackets(),
+                template: self.template(),
             }
         };
         set_name(&self, name);
@@ -91,14 +133,7 @@ pub trait Nameable: Debug + Display {
     where
         Self: Sized,
     {
-        set_name(
-            &self,
-            Name {
-                prefix: self.shortener().map(|s| s.prefix),
-                kind: NameKind::Short(self.short()),
-                brackets: self.brackets(),
-            },
-        );
+        set_name(&self, short_name(&self));
         self
     }
 
@@ -106,15 +141,7 @@ pub trait Nameable: Debug + Display {
     where
         Self: Sized,
     {
-        let serial = SERIAL.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-        set_name(
-            &self,
-            Name {
-                prefix: self.shortener().map(|s| s.prefix),
-                kind: NameKind::Serial(serial),
-                brackets: self.brackets(),
-            },
-        );
+        set_name(&self, serial_name(&self));
         self
     }
 
@@ -129,6 +156,27 @@ pub trait Nameable: Debug + Display {
     fn brackets(&self) -> (&'static str, &'static str) {
         ("⟪", "⟫")
     }
+
+    /// The layout of this type's names, if it should differ from the global [`template`].
+    fn template(&self) -> Option<Template> {
+        None
+    }
+
+    /// How values of this type are named when they're rendered before being named explicitly.
+    /// Defaults to the global [`default_name_kind`](crate::default_name_kind).
+    fn default_name_kind(&self) -> Option<DefaultNameKind> {
+        crate::default_name_kind()
+    }
+
+    /// Visit this value as its [`Rename`] impl does, which by default visits just the value itself.
+    /// Enums deriving `RenameAll` with `#[named_id(name_field = "...")]` visit their fields instead,
+    /// as long as they have no name of their own.
+    fn visit_self<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>))
+    where
+        Self: Sized,
+    {
+        visitor(AnyNameable::borrowed(self))
+    }
 }
 
 pub struct Shortener {
@@ -139,22 +187,26 @@ pub struct Shortener {
 impl Shortener {
     fn shorten(&self, original: String) -> String {
         // assert_prefix_unique(self);
-        let mut short_id = original.clone();
-
-        short_id.truncate(self.length);
+        let end = original
+            .char_indices()
+            .nth(self.length)
+            .map_or(original.len(), |(idx, _)| idx);
+        let short_id = original[..end].to_string();
 
+        if !CHECK_SHORT.get() {
+            return short_id;
+        }
         if let Some(existing) = SHORT_ID_CACHE
             .lock()
             .unwrap()
             .insert(short_id.clone(), original.clone())
+            && existing != original
         {
-            if existing != original {
-                tracing::warn!(
-                    old = ?existing,
-                    new = ?original,
-                    "short ID collision, two values have the same short ID"
-                );
-            }
+            tracing::warn!(
+                old = ?existing,
+                new = ?original,
+                "short ID collision, two values have the same short ID"
+            );
         }
         short_id
     }
@@ -167,25 +219,159 @@ where
     fn shortener(&self) -> Option<Shortener> {
         (*self).shortener()
     }
+
+    fn brackets(&self) -> (&'static str, &'static str) {
+        (*self).brackets()
+    }
+
+    fn template(&self) -> Option<Template> {
+        (*self).template()
+    }
+
+    fn default_name_kind(&self) -> Option<DefaultNameKind> {
+        (*self).default_name_kind()
+    }
+
+    fn visit_self<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        (*self).visit_self(visitor)
+    }
+}
+
+impl<T> Nameable for &mut T
+where
+    T: Nameable,
+{
+    fn shortener(&self) -> Option<Shortener> {
+        (**self).shortener()
+    }
+
+    fn brackets(&self) -> (&'static str, &'static str) {
+        (**self).brackets()
+    }
+
+    fn template(&self) -> Option<Template> {
+        (**self).template()
+    }
+
+    fn default_name_kind(&self) -> Option<DefaultNameKind> {
+        (**self).default_name_kind()
+    }
+
+    fn visit_self<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        (**self).visit_self(visitor)
+    }
+}
+
+impl<T> Nameable for Box<T>
+where
+    T: Nameable + ?Sized,
+{
+    fn shortener(&self) -> Option<Shortener> {
+        (**self).shortener()
+    }
+
+    fn brackets(&self) -> (&'static str, &'static str) {
+        (**self).brackets()
+    }
+
+    fn template(&self) -> Option<Template> {
+        (**self).template()
+    }
+
+    fn default_name_kind(&self) -> Option<DefaultNameKind> {
+        (**self).default_name_kind()
+    }
+}
+
+impl<P> Nameable for std::pin::Pin<P>
+where
+    P: std::ops::Deref + Debug + Display,
+    P::Target: Nameable,
+{
+    fn shortener(&self) -> Option<Shortener> {
+        self.as_ref().get_ref().shortener()
+    }
+
+    fn brackets(&self) -> (&'static str, &'static str) {
+        self.as_ref().get_ref().brackets()
+    }
+
+    fn template(&self) -> Option<Template> {
+        self.as_ref().get_ref().template()
+    }
+
+    fn default_name_kind(&self) -> Option<DefaultNameKind> {
+        self.as_ref().get_ref().default_name_kind()
+    }
 }
 
 impl<T> Rename for T
 where
-    T: Nameable + Clone,
+    T: Nameable,
 {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        vec![AnyNameable(Box::new(self.clone()))]
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        if is_rendering()
+            && let Some(kind) = self.default_name_kind()
+        {
+            auto_name(self, kind);
+        }
+        self.visit_self(visitor)
+    }
+}
+
+fn auto_name<T: Nameable>(id: &T, kind: DefaultNameKind) {
+    if is_named(id) {
+        return;
+    }
+    let name = match kind {
+        DefaultNameKind::Serial => serial_name(id),
+        DefaultNameKind::Short => short_name(id),
+    };
+    set_name(id, name);
+}
+
+fn short_name<T: Nameable + ?Sized>(id: &T) -> Name {
+    Name {
+        prefix: id.shortener().map(|s| s.prefix),
+        kind: NameKind::Short(id.short()),
+        short: None,
+        brackets: id.brackets(),
+        template: id.template(),
+    }
+}
+
+/// The short form of an ID which is only kept in case a [`Mode`] shows it.
+/// It's not checked for collisions, since it may never be shown.
+fn spare_short<T: Nameable + ?Sized>(id: &T) -> Option<String> {
+    id.shortener()?;
+    Some(scoped(&CHECK_SHORT, false, || id.short()))
+}
+
+fn serial_name<T: Nameable + ?Sized>(id: &T) -> Name {
+    let serial = SERIAL.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    Name {
+        prefix: id.shortener().map(|s| s.prefix),
+        kind: NameKind::Serial(serial),
+        short: spare_short(id),
+        brackets: id.brackets(),
+        template: id.template(),
     }
 }
 
-pub(crate) fn get_name_string(id: &AnyNameable) -> String {
+/// The rendered name of a registered ID, given its `Debug` output, or `None` if it has no name.
+pub(crate) fn get_name_string(repr: &str, mode: Mode, coloring: Coloring) -> Option<String> {
     NAMES
         .lock()
         .unwrap()
-        .get(&format!("{id:?}"))
-        .cloned()
-        .map(|name| name.to_string())
-        .unwrap_or_else(|| id.to_string())
+        .get(repr)
+        .map(|name| name.render(mode, coloring, repr))
+}
+
+/// Whether an ID has a registered name.
+pub(crate) fn is_named(id: &dyn Debug) -> bool {
+    // Formatted before locking, so that a panicking `Debug` impl can't poison the names
+    let repr = format!("{id:?}");
+    NAMES.lock().unwrap().contains_key(&repr)
 }
 
 pub(crate) fn set_name(id: &dyn Debug, name: Name) {
@@ -194,14 +380,16 @@ pub(crate) fn set_name(id: &dyn Debug, name: Name) {
     let mut lock = NAMES.lock().unwrap();
 
     // Only replace "upward" in specificity
-    let existing = lock.get(&format!("{id:?}"));
+    let existing = lock.get(&repr);
     let replace = existing
-        .map(|existing| match (&existing.kind, &name.kind) {
-            (Serial(_), Serial(_)) => false,
-            (Short(_), Serial(_) | Short(_)) => false,
-            (Name(_), Serial(_) | Short(_) | Name(_)) => false,
-            (NameShort { .. }, _) => false,
-            _ => true,
+        .map(|existing| {
+            !matches!(
+                (&existing.kind, &name.kind),
+                (Serial(_), Serial(_))
+                    | (Short(_), Serial(_) | Short(_))
+                    | (Name(_), Serial(_) | Short(_) | Name(_))
+                    | (NameShort { .. }, _)
+            )
         })
         .unwrap_or(true);
 
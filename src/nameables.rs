@@ -1,24 +1,62 @@
 use crate::Renamed;
 
-use std::fmt::Debug;
+use std::{collections::HashSet, fmt::Debug};
 
+mod external;
 mod impls;
 
 pub trait AnyNameableBounds<'a>: Debug + 'a {}
 impl<'a, T: Debug + 'a> AnyNameableBounds<'a> for T {}
 
-pub struct AnyNameable<'a>(pub(crate) Box<dyn AnyNameableBounds<'a>>);
+pub struct AnyNameable<'a>(AnyNameableInner<'a>);
+
+enum AnyNameableInner<'a> {
+    Borrowed(&'a (dyn Debug + 'a)),
+    Owned(Box<dyn AnyNameableBounds<'a>>),
+}
 
 impl<'a> AnyNameable<'a> {
     pub fn new<T: AnyNameableBounds<'a>>(t: T) -> Self {
-        AnyNameable(Box::new(t))
+        AnyNameable(AnyNameableInner::Owned(Box::new(t)))
+    }
+
+    /// Refer to a nameable without cloning or allocating.
+    pub fn borrowed<T: Debug + 'a>(t: &'a T) -> Self {
+        AnyNameable(AnyNameableInner::Borrowed(t))
+    }
+
+    /// Capture the `Debug` output of a nameable, so it can outlive the value it came from.
+    /// Only needed for the contents of guards and copies, e.g. of a `Mutex` or `Cell`.
+    pub(crate) fn detached(t: &dyn Debug) -> Self {
+        AnyNameable::new(Detached {
+            debug: format!("{t:?}"),
+            pretty: format!("{t:#?}"),
+        })
+    }
+}
+
+struct Detached {
+    debug: String,
+    pretty: String,
+}
+
+impl Debug for Detached {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            f.write_str(&self.pretty)
+        } else {
+            f.write_str(&self.debug)
+        }
     }
 }
 
 impl<'a> std::ops::Deref for AnyNameable<'a> {
     type Target = dyn Debug + 'a;
     fn deref(&self) -> &Self::Target {
-        &*self.0
+        match &self.0 {
+            AnyNameableInner::Borrowed(t) => *t,
+            AnyNameableInner::Owned(t) => &**t,
+        }
     }
 }
 
@@ -31,24 +69,50 @@ impl<'a> std::fmt::Display for AnyNameable<'a> {
 impl<'a> std::fmt::Debug for AnyNameable<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if f.alternate() {
-            write!(f, "{:#?}", self.0)
+            write!(f, "{:#?}", &**self)
         } else {
-            write!(f, "{:?}", self.0)
+            write!(f, "{:?}", &**self)
         }
     }
 }
 
-pub trait Rename: Sized + Debug {
-    fn nameables(&self) -> Vec<AnyNameable<'_>>;
+pub trait Rename: Debug {
+    /// Call `visitor` with each nameable contained in this value, borrowed from it where possible.
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>));
 
-    fn renamed(self) -> Renamed<Self> {
+    /// Collect the nameables contained in this value.
+    fn nameables(&self) -> Vec<AnyNameable<'_>> {
+        let mut nameables = vec![];
+        self.visit_nameables(&mut |n| nameables.push(n));
+        nameables
+    }
+
+    /// Like [`nameables`](Rename::nameables), but without repeats, compared by `Debug` output.
+    /// The first occurrence of each nameable is kept, in visiting order.
+    fn unique_nameables(&self) -> Vec<AnyNameable<'_>> {
+        let mut seen = HashSet::new();
+        let mut nameables = vec![];
+        self.visit_nameables(&mut |n| {
+            if seen.insert(format!("{n:?}")) {
+                nameables.push(n);
+            }
+        });
+        nameables
+    }
+
+    fn renamed(self) -> Renamed<Self>
+    where
+        Self: Sized,
+    {
         self.into()
     }
 
-    fn renamed_ref(&self) -> Renamed<Self>
+    /// Wrap a borrow, without cloning.
+    /// For unsized types, use [`Renamed::from_ref`].
+    fn renamed_ref(&self) -> &Renamed<Self>
     where
-        Self: Clone,
+        Self: Sized,
     {
-        Renamed::from(self.clone())
+        Renamed::from_ref(self)
     }
 }
@@ -0,0 +1,176 @@
+//! `Rename` and `Nameable` impls for types from other crates, each behind a feature of the same name.
+
+#[allow(unused_imports)]
+use super::*;
+#[allow(unused_imports)]
+use crate::empty_nameables;
+
+#[cfg(feature = "arrayvec")]
+mod arrayvec_impls {
+    use super::*;
+
+    impl<T, const CAP: usize> Rename for arrayvec::ArrayVec<T, CAP>
+    where
+        T: Rename,
+    {
+        fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+            self.iter().for_each(|t| t.visit_nameables(visitor))
+        }
+    }
+
+    impl<const CAP: usize> Rename for arrayvec::ArrayString<CAP> {
+        fn visit_nameables<'a>(&'a self, _visitor: &mut dyn FnMut(AnyNameable<'a>)) {}
+    }
+}
+
+#[cfg(feature = "bytes")]
+mod bytes_impls {
+    use super::*;
+
+    empty_nameables!(bytes::Bytes);
+    empty_nameables!(bytes::BytesMut);
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impls {
+    use super::*;
+
+    empty_nameables!(chrono::NaiveDate);
+    empty_nameables!(chrono::NaiveTime);
+    empty_nameables!(chrono::NaiveDateTime);
+    empty_nameables!(chrono::TimeDelta);
+    empty_nameables!(chrono::Utc);
+    empty_nameables!(chrono::FixedOffset);
+
+    impl<Tz> Rename for chrono::DateTime<Tz>
+    where
+        Tz: chrono::TimeZone,
+        Tz::Offset: std::fmt::Debug,
+    {
+        fn visit_nameables<'a>(&'a self, _visitor: &mut dyn FnMut(AnyNameable<'a>)) {}
+    }
+}
+
+#[cfg(feature = "im")]
+mod im_impls {
+    use super::*;
+
+    impl<T> Rename for im::Vector<T>
+    where
+        T: Rename + Clone,
+    {
+        fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+            self.iter().for_each(|t| t.visit_nameables(visitor))
+        }
+    }
+
+    impl<T, S> Rename for im::HashSet<T, S>
+    where
+        T: Rename + Clone + std::hash::Hash + Eq,
+        S: std::hash::BuildHasher + Default,
+    {
+        fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+            self.iter().for_each(|t| t.visit_nameables(visitor))
+        }
+    }
+
+    impl<T> Rename for im::OrdSet<T>
+    where
+        T: Rename + Clone + Ord,
+    {
+        fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+            self.iter().for_each(|t| t.visit_nameables(visitor))
+        }
+    }
+
+    impl<K, V, S> Rename for im::HashMap<K, V, S>
+    where
+        K: Rename + Clone + std::hash::Hash + Eq,
+        V: Rename + Clone,
+        S: std::hash::BuildHasher + Default,
+    {
+        fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+            for (k, v) in self.iter() {
+                k.visit_nameables(visitor);
+                v.visit_nameables(visitor);
+            }
+        }
+    }
+
+    impl<K, V> Rename for im::OrdMap<K, V>
+    where
+        K: Rename + Clone + Ord,
+        V: Rename + Clone,
+    {
+        fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+            for (k, v) in self.iter() {
+                k.visit_nameables(visitor);
+                v.visit_nameables(visitor);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "indexmap")]
+mod indexmap_impls {
+    use super::*;
+
+    impl<T, S> Rename for indexmap::IndexSet<T, S>
+    where
+        T: Rename,
+    {
+        fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+            self.iter().for_each(|t| t.visit_nameables(visitor))
+        }
+    }
+
+    impl<K, V, S> Rename for indexmap::IndexMap<K, V, S>
+    where
+        K: Rename,
+        V: Rename,
+    {
+        fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+            for (k, v) in self.iter() {
+                k.visit_nameables(visitor);
+                v.visit_nameables(visitor);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "smallvec")]
+mod smallvec_impls {
+    use super::*;
+
+    impl<A> Rename for smallvec::SmallVec<A>
+    where
+        A: smallvec::Array,
+        A::Item: Rename,
+    {
+        fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+            self.iter().for_each(|t| t.visit_nameables(visitor))
+        }
+    }
+}
+
+#[cfg(feature = "url")]
+mod url_impls {
+    use super::*;
+
+    empty_nameables!(url::Url);
+}
+
+#[cfg(feature = "uuid")]
+mod uuid_impls {
+    use crate::{Nameable, Shortener};
+
+    /// UUIDs are shortened to their first 8 hex digits, e.g. `⟪UUID|67e55044⟫`.
+    impl Nameable for uuid::Uuid {
+        fn shortener(&self) -> Option<Shortener> {
+            Some(Shortener {
+                length: 8,
+                prefix: "UUID",
+            })
+        }
+    }
+}
@@ -1,13 +1,15 @@
-use std::marker::PhantomData;
+use std::{fmt::Debug, marker::PhantomData, sync::TryLockError};
 
 use super::*;
 
 #[macro_export]
 macro_rules! empty_nameables {
     ($ty:ty) => {
-        impl Rename for $ty {
-            fn nameables(&self) -> Vec<AnyNameable<'_>> {
-                vec![]
+        impl $crate::Rename for $ty {
+            fn visit_nameables<'a>(
+                &'a self,
+                _visitor: &mut dyn ::std::ops::FnMut($crate::AnyNameable<'a>),
+            ) {
             }
         }
     };
@@ -29,11 +31,65 @@ empty_nameables!(f32);
 empty_nameables!(f64);
 empty_nameables!(bool);
 empty_nameables!(char);
-empty_nameables!(&'static str);
+empty_nameables!(str);
+empty_nameables!(String);
+empty_nameables!(Box<str>);
+empty_nameables!(std::path::Path);
+empty_nameables!(std::path::PathBuf);
+empty_nameables!(std::ffi::OsStr);
+empty_nameables!(std::ffi::OsString);
+empty_nameables!(std::time::Duration);
+empty_nameables!(std::time::Instant);
+empty_nameables!(std::time::SystemTime);
+empty_nameables!(std::net::IpAddr);
+empty_nameables!(std::net::Ipv4Addr);
+empty_nameables!(std::net::Ipv6Addr);
+empty_nameables!(std::net::SocketAddr);
+empty_nameables!(std::net::SocketAddrV4);
+empty_nameables!(std::net::SocketAddrV6);
+empty_nameables!(std::num::NonZeroU8);
+empty_nameables!(std::num::NonZeroU16);
+empty_nameables!(std::num::NonZeroU32);
+empty_nameables!(std::num::NonZeroU64);
+empty_nameables!(std::num::NonZeroU128);
+empty_nameables!(std::num::NonZeroUsize);
+empty_nameables!(std::num::NonZeroI8);
+empty_nameables!(std::num::NonZeroI16);
+empty_nameables!(std::num::NonZeroI32);
+empty_nameables!(std::num::NonZeroI64);
+empty_nameables!(std::num::NonZeroI128);
+empty_nameables!(std::num::NonZeroIsize);
+empty_nameables!(std::cmp::Ordering);
+
+// Borrowed strings and paths get impls of their own, which can't overlap the blanket impl for
+// `Nameable`, since `&T` is only `Nameable` for sized `T`.
+
+impl Rename for &str {
+    fn visit_nameables<'a>(&'a self, _visitor: &mut dyn FnMut(AnyNameable<'a>)) {}
+}
+
+impl Rename for &std::path::Path {
+    fn visit_nameables<'a>(&'a self, _visitor: &mut dyn FnMut(AnyNameable<'a>)) {}
+}
+
+impl Rename for &std::ffi::OsStr {
+    fn visit_nameables<'a>(&'a self, _visitor: &mut dyn FnMut(AnyNameable<'a>)) {}
+}
 
 impl<T> Rename for PhantomData<T> {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        vec![]
+    fn visit_nameables<'a>(&'a self, _visitor: &mut dyn FnMut(AnyNameable<'a>)) {}
+}
+
+impl<T, E> Rename for Result<T, E>
+where
+    T: Rename,
+    E: Rename,
+{
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        match self {
+            Ok(t) => t.visit_nameables(visitor),
+            Err(e) => e.visit_nameables(visitor),
+        }
     }
 }
 
@@ -41,8 +97,8 @@ impl<T> Rename for Option<T>
 where
     T: Rename,
 {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        self.iter().flat_map(|t| t.nameables()).collect()
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        self.iter().for_each(|t| t.visit_nameables(visitor))
     }
 }
 
@@ -50,8 +106,8 @@ impl<T> Rename for Vec<T>
 where
     T: Rename,
 {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        self.iter().flat_map(|t| t.nameables()).collect()
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        self.iter().for_each(|t| t.visit_nameables(visitor))
     }
 }
 
@@ -59,8 +115,8 @@ impl<T> Rename for std::collections::HashSet<T>
 where
     T: Rename,
 {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        self.iter().flat_map(|t| t.nameables()).collect()
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        self.iter().for_each(|t| t.visit_nameables(visitor))
     }
 }
 
@@ -68,8 +124,8 @@ impl<T> Rename for std::collections::BTreeSet<T>
 where
     T: Rename,
 {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        self.iter().flat_map(|t| t.nameables()).collect()
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        self.iter().for_each(|t| t.visit_nameables(visitor))
     }
 }
 
@@ -78,10 +134,11 @@ where
     K: Rename,
     V: Rename,
 {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        self.iter()
-            .flat_map(|(k, v)| k.nameables().into_iter().chain(v.nameables()))
-            .collect()
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        for (k, v) in self.iter() {
+            k.visit_nameables(visitor);
+            v.visit_nameables(visitor);
+        }
     }
 }
 
@@ -90,10 +147,74 @@ where
     K: Rename,
     V: Rename,
 {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        self.iter()
-            .flat_map(|(k, v)| k.nameables().into_iter().chain(v.nameables()))
-            .collect()
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        for (k, v) in self.iter() {
+            k.visit_nameables(visitor);
+            v.visit_nameables(visitor);
+        }
+    }
+}
+
+impl<T> Rename for std::collections::VecDeque<T>
+where
+    T: Rename,
+{
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        self.iter().for_each(|t| t.visit_nameables(visitor))
+    }
+}
+
+impl<T> Rename for std::collections::LinkedList<T>
+where
+    T: Rename,
+{
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        self.iter().for_each(|t| t.visit_nameables(visitor))
+    }
+}
+
+impl<T> Rename for std::collections::BinaryHeap<T>
+where
+    T: Rename,
+{
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        self.iter().for_each(|t| t.visit_nameables(visitor))
+    }
+}
+
+impl<T> Rename for [T]
+where
+    T: Rename,
+{
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        self.iter().for_each(|t| t.visit_nameables(visitor))
+    }
+}
+
+impl<T> Rename for &[T]
+where
+    T: Rename,
+{
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        (**self).visit_nameables(visitor)
+    }
+}
+
+impl<T> Rename for &mut [T]
+where
+    T: Rename,
+{
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        (**self).visit_nameables(visitor)
+    }
+}
+
+impl<T> Rename for Box<[T]>
+where
+    T: Rename,
+{
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        (**self).visit_nameables(visitor)
     }
 }
 
@@ -101,147 +222,164 @@ impl<const N: usize, T> Rename for [T; N]
 where
     T: Rename,
 {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        self.iter().flat_map(|t| t.nameables()).collect()
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        self.iter().for_each(|t| t.visit_nameables(visitor))
+    }
+}
+
+// `Box<T>`, `Pin<P>`, `&T` and `&mut T` can't get a generic `Rename` impl without overlapping
+// the blanket impl for `Nameable`, so they are `Nameable` when their contents are.
+// `#[derive(RenameAll)]` and `#[derive(RenameNone)]` implement `Rename` for pointers to the
+// derived type, fields of these types work through auto-deref, and slices and trait objects
+// behind them have impls of their own. Other pointers, e.g. `Vec<Box<Vec<Id>>>`, aren't `Rename`.
+
+impl<'d> Rename for Box<dyn Rename + 'd> {
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        (**self).visit_nameables(visitor)
+    }
+}
+
+impl<'d> Rename for Box<dyn Rename + Send + 'd> {
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        (**self).visit_nameables(visitor)
     }
 }
 
-impl<A, B> Rename for (A, B)
+impl<'d> Rename for Box<dyn Rename + Send + Sync + 'd> {
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        (**self).visit_nameables(visitor)
+    }
+}
+
+impl<'d> Rename for &(dyn Rename + 'd) {
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        (**self).visit_nameables(visitor)
+    }
+}
+
+impl<T> Rename for std::rc::Rc<T>
 where
-    A: Rename,
-    B: Rename,
+    T: Rename + ?Sized,
 {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        self.0
-            .nameables()
-            .into_iter()
-            .chain(self.1.nameables())
-            .collect()
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        (**self).visit_nameables(visitor)
     }
 }
 
-impl<A, B, C> Rename for (A, B, C)
+impl<T> Rename for std::sync::Arc<T>
 where
-    A: Rename,
-    B: Rename,
-    C: Rename,
+    T: Rename + ?Sized,
 {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        self.0
-            .nameables()
-            .into_iter()
-            .chain(self.1.nameables())
-            .chain(self.2.nameables())
-            .collect()
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        (**self).visit_nameables(visitor)
     }
 }
 
-impl<A, B, C, D> Rename for (A, B, C, D)
+impl<B> Rename for std::borrow::Cow<'_, B>
 where
-    A: Rename,
-    B: Rename,
-    C: Rename,
-    D: Rename,
+    B: Rename + ToOwned + ?Sized,
+    B::Owned: Debug,
 {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        self.0
-            .nameables()
-            .into_iter()
-            .chain(self.1.nameables())
-            .chain(self.2.nameables())
-            .chain(self.3.nameables())
-            .collect()
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        (**self).visit_nameables(visitor)
     }
 }
 
-impl<A, B, C, D, E> Rename for (A, B, C, D, E)
+impl<T> Rename for std::cmp::Reverse<T>
 where
-    A: Rename,
-    B: Rename,
-    C: Rename,
-    D: Rename,
-    E: Rename,
+    T: Rename,
 {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        self.0
-            .nameables()
-            .into_iter()
-            .chain(self.1.nameables())
-            .chain(self.2.nameables())
-            .chain(self.3.nameables())
-            .chain(self.4.nameables())
-            .collect()
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        self.0.visit_nameables(visitor)
     }
 }
 
-impl<A, B, C, D, E, F> Rename for (A, B, C, D, E, F)
+impl<T> Rename for std::num::Wrapping<T>
 where
-    A: Rename,
-    B: Rename,
-    C: Rename,
-    D: Rename,
-    E: Rename,
-    F: Rename,
+    T: Rename,
 {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        self.0
-            .nameables()
-            .into_iter()
-            .chain(self.1.nameables())
-            .chain(self.2.nameables())
-            .chain(self.3.nameables())
-            .chain(self.4.nameables())
-            .chain(self.5.nameables())
-            .collect()
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        self.0.visit_nameables(visitor)
     }
 }
 
-impl<A, B, C, D, E, F, G> Rename for (A, B, C, D, E, F, G)
+// The contents of cells and locks are only reachable through a guard or a copy,
+// which lives just as long as the visit, so their nameables are detached.
+// A value which is already locked or mutably borrowed has nothing to rename,
+// since its `Debug` output doesn't include it either.
+
+fn visit_detached<'a, T: Rename + ?Sized>(t: &T, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+    t.visit_nameables(&mut |n| visitor(AnyNameable::detached(&*n)))
+}
+
+impl<T> Rename for std::cell::Cell<T>
 where
-    A: Rename,
-    B: Rename,
-    C: Rename,
-    D: Rename,
-    E: Rename,
-    F: Rename,
-    G: Rename,
+    T: Rename + Copy,
 {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        self.0
-            .nameables()
-            .into_iter()
-            .chain(self.1.nameables())
-            .chain(self.2.nameables())
-            .chain(self.3.nameables())
-            .chain(self.4.nameables())
-            .chain(self.5.nameables())
-            .chain(self.6.nameables())
-            .collect()
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        visit_detached(&self.get(), visitor)
     }
 }
 
-impl<A, B, C, D, E, F, G, H> Rename for (A, B, C, D, E, F, G, H)
+impl<T> Rename for std::cell::RefCell<T>
 where
-    A: Rename,
-    B: Rename,
-    C: Rename,
-    D: Rename,
-    E: Rename,
-    F: Rename,
-    G: Rename,
-    H: Rename,
-{
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        self.0
-            .nameables()
-            .into_iter()
-            .chain(self.1.nameables())
-            .chain(self.2.nameables())
-            .chain(self.3.nameables())
-            .chain(self.4.nameables())
-            .chain(self.5.nameables())
-            .chain(self.6.nameables())
-            .chain(self.7.nameables())
-            .collect()
+    T: Rename,
+{
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        if let Ok(t) = self.try_borrow() {
+            visit_detached(&*t, visitor)
+        }
     }
 }
+
+impl<T> Rename for std::sync::Mutex<T>
+where
+    T: Rename,
+{
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        match self.try_lock() {
+            Ok(t) => visit_detached(&*t, visitor),
+            Err(TryLockError::Poisoned(err)) => visit_detached(&*err.into_inner(), visitor),
+            Err(TryLockError::WouldBlock) => {}
+        }
+    }
+}
+
+impl<T> Rename for std::sync::RwLock<T>
+where
+    T: Rename,
+{
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        match self.try_read() {
+            Ok(t) => visit_detached(&*t, visitor),
+            Err(TryLockError::Poisoned(err)) => visit_detached(&*err.into_inner(), visitor),
+            Err(TryLockError::WouldBlock) => {}
+        }
+    }
+}
+
+macro_rules! tuple_nameables {
+    ($($name:ident $idx:tt),+) => {
+        impl<$($name),+> Rename for ($($name,)+)
+        where
+            $($name: Rename,)+
+        {
+            fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+                $(self.$idx.visit_nameables(visitor);)+
+            }
+        }
+    };
+}
+
+tuple_nameables!(A 0);
+tuple_nameables!(A 0, B 1);
+tuple_nameables!(A 0, B 1, C 2);
+tuple_nameables!(A 0, B 1, C 2, D 3);
+tuple_nameables!(A 0, B 1, C 2, D 3, E 4);
+tuple_nameables!(A 0, B 1, C 2, D 3, E 4, F 5);
+tuple_nameables!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+tuple_nameables!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+tuple_nameables!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+tuple_nameables!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+tuple_nameables!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+tuple_nameables!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
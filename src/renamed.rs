@@ -1,9 +1,23 @@
+use std::{collections::HashSet, fmt::Debug};
+
 use serde::{Deserialize, Serialize};
 
 use super::*;
 
 #[derive(Clone, Copy, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
-pub struct Renamed<T>(T);
+#[repr(transparent)]
+pub struct Renamed<T: ?Sized>(T);
+
+impl<T> Renamed<T>
+where
+    T: Rename + ?Sized,
+{
+    /// Wrap a borrow, without cloning.
+    pub fn from_ref(value: &T) -> &Self {
+        // SAFETY: `Renamed` is `repr(transparent)`, so it has the same layout as `T`.
+        unsafe { &*(value as *const T as *const Self) }
+    }
+}
 
 impl<T> From<T> for Renamed<T>
 where
@@ -16,16 +30,16 @@ where
 
 impl<T> Rename for Renamed<T>
 where
-    T: Rename,
+    T: Rename + ?Sized,
 {
-    fn nameables(&self) -> Vec<AnyNameable<'_>> {
-        self.0.nameables()
+    fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+        self.0.visit_nameables(visitor)
     }
 }
 
 impl<T> std::ops::Deref for Renamed<T>
 where
-    T: Rename,
+    T: Rename + ?Sized,
 {
     type Target = T;
     fn deref(&self) -> &Self::Target {
@@ -35,7 +49,7 @@ where
 
 impl<T> std::fmt::Display for Renamed<T>
 where
-    T: Rename,
+    T: Rename + ?Sized,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
@@ -44,34 +58,61 @@ where
 
 impl<T> std::fmt::Debug for Renamed<T>
 where
-    T: Rename,
+    T: Rename + ?Sized,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if mode() == Mode::Off {
+            return std::fmt::Debug::fmt(&self.0, f);
+        }
         write!(
             f,
             "{}",
-            rename(&self.0, self.0.nameables().as_slice(), f.alternate())
+            render(&self.0, f.alternate(), |visitor| rendering(|| self
+                .0
+                .visit_nameables(&mut |n| visitor(&*n))))
         )
     }
 }
 
-pub fn rename<T: std::fmt::Debug>(t: &T, nameables: &[AnyNameable], pretty: bool) -> String {
+pub fn rename<T: Debug + ?Sized>(t: &T, nameables: &[AnyNameable], pretty: bool) -> String {
+    render(t, pretty, |visitor| {
+        nameables.iter().for_each(|id| visitor(&**id))
+    })
+}
+
+fn render<T: Debug + ?Sized>(
+    t: &T,
+    pretty: bool,
+    visit: impl FnOnce(&mut dyn FnMut(&dyn Debug)),
+) -> String {
     let debug = if pretty {
         format!("{:#?}", t)
     } else {
         format!("{:?}", t)
     };
-    let patterns = nameables
-        .into_iter()
-        .map(|id| {
-            let pat = if pretty {
-                pretty_pattern(&format!("{:#?}", id))
-            } else {
-                format!("{:?}", id)
-            };
-            (pat, get_name_string(&id))
-        })
-        .collect::<Vec<_>>();
+    let mode = mode();
+    if mode == Mode::Off {
+        return debug;
+    }
+    // The same ID often appears many times, e.g. as map keys, but needs only one pass.
+    let mut seen = HashSet::new();
+    let mut patterns = vec![];
+    visit(&mut |id| {
+        let repr = format!("{:?}", id);
+        if !seen.insert(repr.clone()) {
+            return;
+        }
+        // Unnamed IDs are left as they are, without building a pattern for them.
+        let Some(name) = get_name_string(&repr, mode, coloring()) else {
+            return;
+        };
+        let pat = if pretty {
+            pretty_pattern(&format!("{:#?}", id))
+        } else {
+            repr
+        };
+        patterns.push((pat, name));
+    });
 
     let mut result = debug;
     for (pattern, replacement) in patterns {
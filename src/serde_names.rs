@@ -0,0 +1,146 @@
+//! Serde helpers which write IDs as their names, for the same readability in JSON logs as in
+//! `Debug` output. `#[serde(with = "named_id::serde_names")]` does so for a [`Nameable`] field,
+//! [`option`], [`seq`] and [`map_keys`] for IDs in an `Option`, a list or set, or a map's keys,
+//! and wrapping an ID in [`Named`] does so anywhere else.
+//! Types nested in a field write their own IDs as names by using these helpers for their fields.
+//!
+//! An ID with a name is written as a string, and one without as it would be otherwise,
+//! so the same field can be e.g. a string or a number, depending on whether the ID was named.
+//!
+//! ```
+//! use named_id::*;
+//!
+//! #[derive(Debug, serde::Serialize)]
+//! struct UserId(u64);
+//!
+//! impl std::fmt::Display for UserId {
+//!     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//!         write!(f, "{}", self.0)
+//!     }
+//! }
+//!
+//! impl Nameable for UserId {
+//!     fn shortener(&self) -> Option<Shortener> {
+//!         Some(Shortener { length: 4, prefix: "U" })
+//!     }
+//! }
+//!
+//! #[derive(serde::Serialize)]
+//! struct Event {
+//!     #[serde(with = "named_id::serde_names")]
+//!     user: UserId,
+//!     #[serde(with = "named_id::serde_names::seq")]
+//!     watchers: Vec<UserId>,
+//!     retries: u8,
+//! }
+//!
+//! let event = Event {
+//!     user: UserId(1234567890).with_name("alice"),
+//!     watchers: vec![UserId(2345678901).with_name("bob"), UserId(3456789012)],
+//!     retries: 2,
+//! };
+//! assert_eq!(
+//!     serde_json::to_string(&event).unwrap(),
+//!     r#"{"user":"⟪U|alice⟫","watchers":["⟪U|bob⟫",3456789012],"retries":2}"#
+//! );
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Coloring, Mode, Nameable, Rename, get_name_string, mode, rendering};
+
+/// Serializes a [`Nameable`] as its name, or as itself if it has none.
+/// Names are rendered in the current [`Mode`], without colors.
+#[derive(Clone, Copy, Debug)]
+pub struct Named<T>(pub T);
+
+impl<T> Serialize for Named<T>
+where
+    T: Nameable + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match name(&self.0) {
+            Some(name) => serializer.serialize_str(&name),
+            None => self.0.serialize(serializer),
+        }
+    }
+}
+
+/// Serialize the value as its name, like [`Named`].
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Nameable + Serialize,
+    S: Serializer,
+{
+    Named(value).serialize(serializer)
+}
+
+/// Deserialize the value as usual, since names can't be turned back into IDs.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer)
+}
+
+/// For an `Option` of a [`Nameable`], e.g. `#[serde(with = "named_id::serde_names::option")]`.
+pub mod option {
+    use super::*;
+
+    pub use super::deserialize;
+
+    /// Serialize the contents as their name, like [`Named`].
+    pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Nameable + Serialize,
+        S: Serializer,
+    {
+        value.as_ref().map(Named).serialize(serializer)
+    }
+}
+
+/// For a list or set of [`Nameable`]s, e.g. `#[serde(with = "named_id::serde_names::seq")]`.
+pub mod seq {
+    use super::*;
+
+    pub use super::deserialize;
+
+    /// Serialize each item as its name, like [`Named`].
+    pub fn serialize<'a, C, T, S>(items: &'a C, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        &'a C: IntoIterator<Item = &'a T>,
+        T: Nameable + Serialize + 'a,
+        S: Serializer,
+    {
+        serializer.collect_seq(items.into_iter().map(Named))
+    }
+}
+
+/// For a map with [`Nameable`] keys, e.g. `#[serde(with = "named_id::serde_names::map_keys")]`.
+pub mod map_keys {
+    use super::*;
+
+    pub use super::deserialize;
+
+    /// Serialize each key as its name, like [`Named`], and each value as usual.
+    pub fn serialize<'a, M, K, V, S>(map: &'a M, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+        K: Nameable + Serialize + 'a,
+        V: Serialize + 'a,
+        S: Serializer,
+    {
+        serializer.collect_map(map.into_iter().map(|(k, v)| (Named(k), v)))
+    }
+}
+
+fn name<T: Nameable>(value: &T) -> Option<String> {
+    let mode = mode();
+    if mode == Mode::Off {
+        return None;
+    }
+    // Visiting while rendering names the value on first sight, as `Renamed` would
+    rendering(|| value.visit_nameables(&mut |_| {}));
+    get_name_string(&format!("{value:?}"), mode, Coloring::Off)
+}
@@ -0,0 +1,63 @@
+use std::sync::{LazyLock, RwLock};
+
+/// When this environment variable is set to anything but an empty string or `0`,
+/// the initial [`Style`] has [`Style::ascii`] enabled.
+pub const ASCII_ENV_VAR: &str = "NAMED_ID_ASCII";
+
+static STYLE: LazyLock<RwLock<Style>> = LazyLock::new(|| {
+    let ascii = std::env::var(ASCII_ENV_VAR)
+        .map(|v| !v.is_empty() && v != "0")
+        .unwrap_or(false);
+    RwLock::new(Style {
+        ascii,
+        ..Style::DEFAULT
+    })
+});
+
+/// Process-wide presentation settings, which take precedence over those of each [`Nameable`](crate::Nameable).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Style {
+    /// Brackets to use for every name instead of [`Nameable::brackets`](crate::Nameable::brackets).
+    pub brackets: Option<(&'static str, &'static str)>,
+    /// The separator substituted for `{sep}` in a [`Template`](crate::Template).
+    pub separator: &'static str,
+    /// Use `<` and `>` in place of any brackets which aren't plain ASCII.
+    pub ascii: bool,
+}
+
+impl Style {
+    /// `⟪ID|foo⟫`
+    pub const DEFAULT: Style = Style {
+        brackets: None,
+        separator: "|",
+        ascii: false,
+    };
+
+    pub(crate) fn brackets(
+        &self,
+        brackets: (&'static str, &'static str),
+    ) -> (&'static str, &'static str) {
+        let (b1, b2) = self.brackets.unwrap_or(brackets);
+        if self.ascii && !(b1.is_ascii() && b2.is_ascii()) {
+            ("<", ">")
+        } else {
+            (b1, b2)
+        }
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The current style, initially taking [`Style::ascii`] from [`ASCII_ENV_VAR`].
+pub fn style() -> Style {
+    *STYLE.read().unwrap()
+}
+
+/// Change the style for the whole process, e.g. to render `<ID:foo>` everywhere.
+pub fn set_style(style: Style) {
+    *STYLE.write().unwrap() = style;
+}
@@ -0,0 +1,91 @@
+use std::sync::RwLock;
+
+static TEMPLATE: RwLock<Template> = RwLock::new(Template::DEFAULT);
+
+/// The layout of a rendered name, excluding its brackets.
+///
+/// Each layout is a string with `{placeholder}`s. These are substituted in a single pass,
+/// so braces within a name are never mistaken for placeholders.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Template {
+    /// How a prefix is attached to a name, using `{prefix}`, `{name}` and `{sep}`.
+    pub prefixed: &'static str,
+    /// How a name and a short ID are shown together, using `{name}`, `{short}` and `{sep}`.
+    pub name_short: &'static str,
+    /// How a serial number is shown, using `{serial}`.
+    pub serial: &'static str,
+    /// The width serial numbers are zero-padded to.
+    pub serial_width: usize,
+}
+
+impl Template {
+    /// `⟪ID|foo⟫`, `⟪ID|foo|1234⟫`, `⟪ID|#001⟫`
+    pub const DEFAULT: Template = Template {
+        prefixed: "{prefix}{sep}{name}",
+        name_short: "{name}{sep}{short}",
+        serial: "#{serial}",
+        serial_width: 3,
+    };
+
+    pub(crate) fn prefixed(&self, sep: &str, prefix: &str, name: &str) -> String {
+        fill(
+            self.prefixed,
+            &[("prefix", prefix), ("name", name), ("sep", sep)],
+        )
+    }
+
+    pub(crate) fn name_short(&self, sep: &str, name: &str, short: &str) -> String {
+        fill(
+            self.name_short,
+            &[("name", name), ("short", short), ("sep", sep)],
+        )
+    }
+
+    pub(crate) fn serial(&self, serial: usize) -> String {
+        let serial = format!("{:0width$}", serial, width = self.serial_width);
+        fill(self.serial, &[("serial", &serial)])
+    }
+}
+
+impl Default for Template {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The template used for any [`Nameable`](crate::Nameable) which doesn't specify its own.
+pub fn template() -> Template {
+    *TEMPLATE.read().unwrap()
+}
+
+/// Change the template for the whole process.
+/// Names are laid out when rendered, so this also applies to names registered earlier.
+pub fn set_template(template: Template) {
+    *TEMPLATE.write().unwrap() = template;
+}
+
+fn fill(layout: &str, args: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(layout.len());
+    let mut rest = layout;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let arg = rest.find('}').and_then(|end| {
+            args.iter()
+                .find(|(key, _)| *key == &rest[1..end])
+                .map(|(_, value)| (end, value))
+        });
+        match arg {
+            Some((end, value)) => {
+                out.push_str(value);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::*;
+use named_id::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_default_name_kind() {
+    let v = vec![Num(81818181), Num(82828282).with_name("named")];
+    assert_eq!(default_name_kind(), None);
+    assert_eq!(
+        format!("{:?}", v.renamed_ref()),
+        "[Num(81818181), ⟪ID|named⟫]"
+    );
+
+    // Collecting nameables doesn't name anything, only rendering does.
+    set_default_name_kind(Some(DefaultNameKind::Short));
+    assert_eq!(v.nameables().len(), 2);
+    set_default_name_kind(None);
+    assert_eq!(
+        format!("{:?}", v.renamed_ref()),
+        "[Num(81818181), ⟪ID|named⟫]"
+    );
+
+    set_default_name_kind(Some(DefaultNameKind::Short));
+    assert_eq!(format!("{:?}", v.renamed_ref()), "[⟪ID|8181⟫, ⟪ID|named⟫]");
+
+    // Once named, IDs keep their name even when auto-naming is turned off.
+    set_default_name_kind(None);
+    let w = (Num(81818181), Num(83838383));
+    assert_eq!(
+        format!("{:?}", w.renamed_ref()),
+        "(⟪ID|8181⟫, Num(83838383))"
+    );
+
+    set_default_name_kind(Some(DefaultNameKind::Serial));
+    let rendered = format!("{:?}", w.renamed_ref());
+    assert!(rendered.starts_with("(⟪ID|8181⟫, ⟪ID|#"), "{rendered}");
+
+    // Enums are named like their `name_field`, or else their fields are named.
+    #[derive(Debug, derive_more::Display, RenameAll)]
+    enum Op {
+        #[named_id(name_field = "0")]
+        #[display("create {_0}")]
+        Create(Num),
+        #[display("drop {_0}")]
+        Drop(Num),
+    }
+
+    set_default_name_kind(Some(DefaultNameKind::Short));
+    let ops = (Op::Create(Num(84848484)), Op::Drop(Num(85858585)));
+    assert_eq!(
+        format!("{:?}", ops.renamed_ref()),
+        "(⟪ID|8484⟫, Drop(⟪ID|8585⟫))"
+    );
+
+    // A panic while rendering doesn't leave auto-naming on for plain collection.
+    #[derive(derive_more::Display)]
+    #[display("flaky")]
+    struct Flaky(std::sync::atomic::AtomicUsize);
+
+    impl std::fmt::Debug for Flaky {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            // The first call formats the whole value, the second is while visiting it.
+            if self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed) > 0 {
+                panic!("flaky Debug impl");
+            }
+            f.write_str("Flaky")
+        }
+    }
+
+    impl Nameable for Flaky {
+        fn shortener(&self) -> Option<Shortener> {
+            None
+        }
+    }
+
+    let flaky = Flaky(0.into());
+    let panicked = std::panic::catch_unwind(|| format!("{:?}", flaky.renamed_ref()));
+    assert!(panicked.is_err());
+    let unnamed = Num(86868686);
+    assert_eq!(unnamed.nameables().len(), 1);
+    set_default_name_kind(None);
+    assert_eq!(format!("{:?}", unnamed.renamed_ref()), "Num(86868686)");
+}
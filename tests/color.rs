@@ -0,0 +1,37 @@
+mod common;
+
+use common::*;
+use named_id::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_coloring() {
+    let a = Num(1111111111).with_name("a");
+    let b = Num(2222222222).with_name("b");
+    let h = Hex([1; 32]).with_name("h");
+
+    assert_eq!(coloring(), Coloring::Off);
+    assert_eq!(format!("{:?}", (&a, &b).renamed()), "(⟪ID|a⟫, ⟪ID|b⟫)");
+
+    if std::env::var_os(NO_COLOR_ENV_VAR).is_some_and(|v| !v.is_empty()) {
+        set_coloring(Coloring::ById);
+        assert_eq!(coloring(), Coloring::Off);
+        assert_eq!(format!("{:?}", (&a, &b).renamed()), "(⟪ID|a⟫, ⟪ID|b⟫)");
+        set_coloring(Coloring::Off);
+        return;
+    }
+
+    set_coloring(Coloring::ByPrefix);
+    assert_eq!(
+        format!("{:?}", (&a, &b, &h).renamed()),
+        "(\x1b[35m⟪ID|a⟫\x1b[0m, \x1b[35m⟪ID|b⟫\x1b[0m, \x1b[92m⟪X|h⟫\x1b[0m)"
+    );
+
+    set_coloring(Coloring::ById);
+    assert_eq!(
+        format!("{:?}", (&a, &b, &h).renamed()),
+        "(\x1b[33m⟪ID|a⟫\x1b[0m, \x1b[93m⟪ID|b⟫\x1b[0m, \x1b[36m⟪X|h⟫\x1b[0m)"
+    );
+
+    set_coloring(Coloring::Off);
+}
@@ -0,0 +1,47 @@
+#![allow(dead_code)]
+
+use std::fmt::Display;
+
+use named_id::*;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, derive_more::Display)]
+pub struct Num(pub u64);
+
+impl Num {
+    pub fn sh(n: u64) -> Self {
+        Self(n).with_short()
+    }
+}
+
+impl Nameable for Num {
+    fn shortener(&self) -> Option<Shortener> {
+        Some(Shortener {
+            length: 4,
+            prefix: "ID",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hex(pub [u8; 32]);
+
+impl Hex {
+    pub fn sh(n: u8) -> Self {
+        Self([n; 32]).with_short()
+    }
+}
+
+impl Nameable for Hex {
+    fn shortener(&self) -> Option<Shortener> {
+        Some(Shortener {
+            length: 4,
+            prefix: "X",
+        })
+    }
+}
+
+impl Display for Hex {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.write_str(&hex::encode(self.0))
+    }
+}
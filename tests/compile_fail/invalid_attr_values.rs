@@ -0,0 +1,53 @@
+use named_id::RenameAll;
+
+fn visit(_: &u32) -> Vec<named_id::AnyNameable<'_>> {
+    vec![]
+}
+
+#[derive(Debug, RenameAll)]
+#[named_id(bound = "T Rename")]
+struct BadBound<T> {
+    t: T,
+}
+
+#[derive(Debug, RenameAll)]
+#[named_id(crate = 42)]
+struct BadCrate;
+
+#[derive(Debug, RenameAll)]
+struct Conflicting {
+    #[named_id(skip, with = visit)]
+    a: u32,
+}
+
+#[derive(Debug, RenameAll)]
+struct IncludeWithoutOnly {
+    #[named_id(include)]
+    a: u32,
+}
+
+#[derive(Clone, Copy, RenameAll)]
+union NoActive {
+    a: u32,
+}
+
+#[derive(Clone, Copy, RenameAll)]
+#[named_id(active = NotUnsafe::active)]
+union NotUnsafe {
+    a: u32,
+}
+
+#[derive(Debug, RenameAll)]
+#[named_id(only)]
+struct SkipWithOnly {
+    #[named_id(skip)]
+    a: u32,
+}
+
+#[derive(Debug, RenameAll)]
+enum MissingNameField {
+    #[named_id(name_field = "id")]
+    A(u32),
+}
+
+fn main() {}
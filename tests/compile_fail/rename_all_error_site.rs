@@ -9,4 +9,10 @@ struct MyStruct {
     bad: NotRenameable,
 }
 
+#[derive(Debug, RenameAll)]
+enum MyEnum {
+    Good(Box<Vec<u32>>),
+    Bad(u32, NotRenameable),
+}
+
 fn main() {}
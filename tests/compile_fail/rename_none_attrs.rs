@@ -0,0 +1,19 @@
+use named_id::RenameNone;
+
+#[derive(Debug, RenameNone)]
+#[named_id(only, bound = "")]
+struct Container {
+    #[named_id(skp)]
+    typo: u32,
+    #[named_id(skip)]
+    skipped: u32,
+}
+
+#[derive(Debug, RenameNone)]
+enum Variants {
+    #[named_id(skip)]
+    A(u32),
+    B(#[named_id(with = visit)] u32),
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+use named_id::{RenameAll, RenameNone};
+
+#[derive(Debug, RenameAll)]
+#[named_id(crate = "named_id", bounds = "")]
+struct Container {
+    #[named_id(skp)]
+    typo: u32,
+    #[named_id(with)]
+    missing_fn: u32,
+}
+
+#[derive(Debug, RenameAll)]
+enum Variants {
+    #[named_id(with = visit)]
+    A(u32),
+    B(#[named_id(skip, skip)] u32),
+}
+
+#[derive(Debug, RenameNone)]
+#[named_id(skip)]
+struct Nothing;
+
+fn main() {}
@@ -0,0 +1,29 @@
+use std::{fmt, marker::PhantomData};
+
+use named_id::{Rename, RenameNone};
+
+struct NotDebug;
+
+#[derive(RenameNone)]
+struct Handle<T>(PhantomData<T>);
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Handle")
+    }
+}
+
+impl<U> fmt::Debug for PhantomOnly<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PhantomOnly")
+    }
+}
+
+#[derive(RenameNone)]
+#[named_id(bound = "")]
+struct PhantomOnly<U>(PhantomData<U>);
+
+fn main() {
+    assert!(Handle::<NotDebug>(PhantomData).nameables().is_empty());
+    assert!(PhantomOnly::<NotDebug>(PhantomData).nameables().is_empty());
+}
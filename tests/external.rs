@@ -0,0 +1,87 @@
+#![allow(unused_imports)]
+
+mod common;
+
+use common::*;
+use named_id::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+#[cfg(feature = "uuid")]
+fn test_uuid() {
+    let id = uuid::Uuid::from_u128(0x67e55044_10b1_426f_9247_bb680e5fe0c8).with_short();
+    assert_eq!(format!("{:?}", id.renamed()), "⟪UUID|67e55044⟫");
+
+    id_spec!(Session, "S", 6);
+    let session = UuidId::<Session>::from(id).with_name("login");
+    assert_eq!(
+        format!("{session:?}"),
+        "S(67e55044-10b1-426f-9247-bb680e5fe0c8)"
+    );
+    assert_eq!(format!("{:?}", session.renamed()), "⟪S|login⟫");
+}
+
+#[test]
+#[cfg(all(feature = "indexmap", feature = "smallvec", feature = "chrono"))]
+fn test_containers() {
+    #[derive(Debug, RenameAll)]
+    struct Index {
+        map: indexmap::IndexMap<Num, smallvec::SmallVec<[Hex; 2]>>,
+        updated: chrono::DateTime<chrono::Utc>,
+    }
+
+    let index = Index {
+        map: indexmap::IndexMap::from([(
+            Num::sh(31313131),
+            smallvec::SmallVec::from([Hex::sh(32), Hex::sh(33)]),
+        )]),
+        updated: chrono::DateTime::UNIX_EPOCH,
+    };
+    assert_eq!(
+        format!("{:?}", index.renamed()),
+        "Index { map: {⟪ID|3131⟫: [⟪X|2020⟫, ⟪X|2121⟫]}, updated: 1970-01-01T00:00:00Z }"
+    );
+}
+
+#[test]
+#[cfg(feature = "arrayvec")]
+fn test_arrayvec() {
+    let mut ids = arrayvec::ArrayVec::<Num, 2>::new();
+    ids.push(Num::sh(34343434));
+    let label = arrayvec::ArrayString::<8>::from("peer").unwrap();
+    assert_eq!(
+        format!("{:?}", (ids, label).renamed()),
+        r#"([⟪ID|3434⟫], "peer")"#
+    );
+}
+
+#[test]
+#[cfg(feature = "bytes")]
+fn test_bytes() {
+    let payload = (Num::sh(35353535), bytes::Bytes::from_static(b"abc"));
+    assert_eq!(format!("{:?}", payload.renamed()), r#"(⟪ID|3535⟫, b"abc")"#);
+}
+
+#[test]
+#[cfg(feature = "url")]
+fn test_url() {
+    let peer = (
+        Num::sh(36363636),
+        url::Url::parse("https://example.com/").unwrap(),
+    );
+    assert_eq!(
+        format!("{:?}", peer.renamed()),
+        "(⟪ID|3636⟫, Url { scheme: \"https\", cannot_be_a_base: false, username: \"\", password: None, host: Some(Domain(\"example.com\")), port: None, path: \"/\", query: None, fragment: None })"
+    );
+}
+
+#[test]
+#[cfg(feature = "im")]
+fn test_im() {
+    let ids = im::Vector::from(vec![Num::sh(37373737)]);
+    let map: im::OrdMap<Num, Hex> = im::OrdMap::from(vec![(Num::sh(38383838), Hex::sh(39))]);
+    assert_eq!(
+        format!("{:?}", (ids, map).renamed()),
+        "([⟪ID|3737⟫], {⟪ID|3838⟫: ⟪X|2727⟫})"
+    );
+}
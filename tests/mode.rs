@@ -0,0 +1,56 @@
+mod common;
+
+use common::*;
+use named_id::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_modes() {
+    let v = vec![
+        Num(1234567890).with_name("foo"),
+        Num(2345678901).with_short(),
+        Num(3456789012).with_name_and_short("baz"),
+        Num(4567890123),
+    ];
+
+    assert_eq!(mode(), Mode::Name);
+    assert_eq!(
+        format!("{:?}", v.renamed_ref()),
+        "[⟪ID|foo⟫, ⟪ID|2345⟫, ⟪ID|baz|3456⟫, Num(4567890123)]"
+    );
+
+    set_mode(Mode::Off);
+    assert_eq!(
+        format!("{:?}", v.renamed_ref()),
+        "[Num(1234567890), Num(2345678901), Num(3456789012), Num(4567890123)]"
+    );
+
+    set_mode(Mode::Short);
+    assert_eq!(
+        format!("{:?}", v.renamed_ref()),
+        "[⟪ID|1234⟫, ⟪ID|2345⟫, ⟪ID|3456⟫, Num(4567890123)]"
+    );
+
+    set_mode(Mode::NameShort);
+    assert_eq!(
+        format!("{:?}", v.renamed_ref()),
+        "[⟪ID|foo|1234⟫, ⟪ID|2345⟫, ⟪ID|baz|3456⟫, Num(4567890123)]"
+    );
+
+    set_mode(Mode::FullName);
+    assert_eq!(
+        format!("{:?}", v.renamed_ref()),
+        "[⟪ID|foo⟫ (Num(1234567890)), ⟪ID|2345⟫ (Num(2345678901)), ⟪ID|baz|3456⟫ (Num(3456789012)), Num(4567890123)]"
+    );
+
+    set_mode(Mode::Name);
+}
+
+#[test]
+fn test_parse_mode() {
+    for mode in ["off", "short", "name", "name+short", "full+name"] {
+        assert_eq!(mode.parse::<Mode>().unwrap().to_string(), mode);
+    }
+    assert_eq!(" Name+Short ".parse(), Ok(Mode::NameShort));
+    assert!("names".parse::<Mode>().is_err());
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use common::*;
+use named_id::*;
+use pretty_assertions::assert_eq;
+
+#[derive(Debug, Clone, derive_more::Display)]
+struct Agent(u64);
+
+impl Nameable for Agent {
+    fn shortener(&self) -> Option<Shortener> {
+        Some(Shortener {
+            length: 3,
+            prefix: "AG",
+        })
+    }
+
+    fn brackets(&self) -> (&'static str, &'static str) {
+        ("«", "»")
+    }
+}
+
+#[test]
+fn test_style() {
+    let v = (
+        Num(1234567890).with_name("foo"),
+        Num(2345678901).with_name_and_short("bar"),
+        Agent(456789).with_name("alice"),
+    );
+    assert_eq!(
+        format!("{:?}", v.renamed_ref()),
+        "(⟪ID|foo⟫, ⟪ID|bar|2345⟫, «AG|alice»)"
+    );
+
+    set_style(Style {
+        ascii: true,
+        ..Style::DEFAULT
+    });
+    assert_eq!(
+        format!("{:?}", v.renamed_ref()),
+        "(<ID|foo>, <ID|bar|2345>, <AG|alice>)"
+    );
+
+    set_style(Style {
+        brackets: Some(("[", "]")),
+        separator: ":",
+        ascii: true,
+    });
+    assert_eq!(
+        format!("{:?}", v.renamed_ref()),
+        "([ID:foo], [ID:bar:2345], [AG:alice])"
+    );
+
+    set_style(Style::DEFAULT);
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::*;
+use named_id::*;
+use pretty_assertions::assert_eq;
+
+#[derive(Debug, Clone, derive_more::Display)]
+struct Agent(u64);
+
+impl Nameable for Agent {
+    fn shortener(&self) -> Option<Shortener> {
+        Some(Shortener {
+            length: 3,
+            prefix: "AG",
+        })
+    }
+
+    fn template(&self) -> Option<Template> {
+        Some(Template {
+            prefixed: "{name}",
+            name_short: "{name}@{short}",
+            ..Template::DEFAULT
+        })
+    }
+}
+
+#[test]
+fn test_templates() {
+    let v = (
+        Num(1234567890).with_name("foo"),
+        Num(2345678901).with_name_and_short("bar"),
+        Num(3456789012).with_serial(),
+        Agent(456789).with_name_and_short("alice"),
+    );
+    let serial = format!("{:?}", v.2.renamed_ref());
+    let serial = &serial["⟪ID|#".len()..serial.len() - "⟫".len()];
+    assert_eq!(serial.len(), 3);
+
+    assert_eq!(
+        format!("{:?}", v.renamed_ref()),
+        format!("(⟪ID|foo⟫, ⟪ID|bar|2345⟫, ⟪ID|#{serial}⟫, ⟪alice@456⟫)")
+    );
+
+    set_template(Template {
+        prefixed: "{prefix}:{name}",
+        name_short: "{name}/{short}",
+        serial: "n{serial}",
+        serial_width: 5,
+    });
+    assert_eq!(
+        format!("{:?}", v.renamed_ref()),
+        format!("(⟪ID:foo⟫, ⟪ID:bar/2345⟫, ⟪ID:n00{serial}⟫, ⟪alice@456⟫)")
+    );
+
+    set_template(Template::DEFAULT);
+}
@@ -1,50 +1,11 @@
-use std::{collections::HashMap, fmt::Display};
+mod common;
 
+use std::collections::HashMap;
+
+use common::*;
 use named_id::*;
 use pretty_assertions::assert_eq;
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, derive_more::Display)]
-struct Num(u64);
-
-impl Num {
-    pub fn sh(n: u64) -> Self {
-        Self(n).with_short()
-    }
-}
-
-impl Nameable for Num {
-    fn shortener(&self) -> Option<Shortener> {
-        Some(Shortener {
-            length: 4,
-            prefix: "ID",
-        })
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Hex([u8; 32]);
-
-impl Hex {
-    pub fn sh(n: u8) -> Self {
-        Self([n; 32]).with_short()
-    }
-}
-
-impl Nameable for Hex {
-    fn shortener(&self) -> Option<Shortener> {
-        Some(Shortener {
-            length: 4,
-            prefix: "X",
-        })
-    }
-}
-
-impl Display for Hex {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        fmt.write_str(&hex::encode(self.0))
-    }
-}
-
 #[test]
 fn test_short_id() {
     tracing_subscriber::fmt::init();
@@ -361,3 +322,782 @@ C {
         .trim()
     );
 }
+
+#[test]
+fn test_std_wrappers() {
+    use std::{
+        borrow::Cow,
+        cell::RefCell,
+        cmp::Reverse,
+        collections::VecDeque,
+        rc::Rc,
+        sync::{Arc, Mutex, RwLock},
+    };
+
+    #[derive(Debug, RenameAll)]
+    struct State {
+        boxed: Box<Num>,
+        inner: Box<Inner>,
+        rc: Rc<Num>,
+        arc: Arc<Vec<Num>>,
+        cow: Cow<'static, Num>,
+        queue: VecDeque<Reverse<Num>>,
+        result: Result<Num, Hex>,
+        mutex: Mutex<Num>,
+        lock: RwLock<Option<Num>>,
+        cell: RefCell<Num>,
+    }
+
+    #[derive(Debug, RenameAll)]
+    struct Inner {
+        id: Num,
+    }
+
+    let state = State {
+        boxed: Box::new(Num::sh(10101010)),
+        inner: Box::new(Inner {
+            id: Num::sh(20202020),
+        }),
+        rc: Rc::new(Num::sh(30303030)),
+        arc: Arc::new(vec![Num::sh(40404040)]),
+        cow: Cow::Owned(Num::sh(50505050)),
+        queue: VecDeque::from([Reverse(Num::sh(60606060))]),
+        result: Err(Hex::sh(7)),
+        mutex: Mutex::new(Num::sh(80808080)),
+        lock: RwLock::new(Some(Num::sh(90909090))),
+        cell: RefCell::new(Num::sh(12121212)),
+    }
+    .renamed();
+    assert_eq!(
+        format!("{state:?}"),
+        "State { boxed: ⟪ID|1010⟫, inner: Inner { id: ⟪ID|2020⟫ }, rc: ⟪ID|3030⟫, arc: [⟪ID|4040⟫], cow: ⟪ID|5050⟫, queue: [Reverse(⟪ID|6060⟫)], result: Err(⟪X|0707⟫), mutex: Mutex { data: ⟪ID|8080⟫, poisoned: false, .. }, lock: RwLock { data: Some(⟪ID|9090⟫), poisoned: false, .. }, cell: RefCell { value: ⟪ID|1212⟫ } }"
+    );
+
+    let _guard = state.mutex.lock().unwrap();
+    let _borrow = state.cell.borrow_mut();
+    let renamed = format!("{state:#?}");
+    assert!(renamed.contains("<locked>"));
+    assert!(renamed.contains("<borrowed>"));
+    assert!(renamed.contains("⟪ID|9090⟫"));
+}
+
+#[test]
+fn test_pointers_to_derived_types() {
+    use std::pin::Pin;
+
+    #[derive(Debug, RenameAll)]
+    struct Node {
+        id: Num,
+        next: Option<Box<Node>>,
+    }
+
+    #[derive(Debug, RenameAll)]
+    struct Inner {
+        id: Num,
+    }
+
+    #[allow(clippy::vec_box)]
+    #[derive(Debug, RenameAll)]
+    struct Holder<'a> {
+        boxed: Vec<Box<Inner>>,
+        pinned: Pin<Box<Inner>>,
+        borrowed: Vec<&'a Inner>,
+    }
+
+    let list = Node {
+        id: Num::sh(64646464),
+        next: Some(Box::new(Node {
+            id: Num::sh(65656565),
+            next: None,
+        })),
+    };
+    assert_eq!(
+        format!("{:?}", list.renamed()),
+        "Node { id: ⟪ID|6464⟫, next: Some(Node { id: ⟪ID|6565⟫, next: None }) }"
+    );
+
+    let inner = Inner {
+        id: Num::sh(67676767),
+    };
+    let holder = Holder {
+        boxed: vec![Box::new(Inner {
+            id: Num::sh(68686868),
+        })],
+        pinned: Box::pin(Inner {
+            id: Num::sh(69696969),
+        }),
+        borrowed: vec![&inner],
+    };
+    assert_eq!(
+        format!("{:?}", holder.renamed()),
+        "Holder { boxed: [Inner { id: ⟪ID|6868⟫ }], pinned: Inner { id: ⟪ID|6969⟫ }, borrowed: [Inner { id: ⟪ID|6767⟫ }] }"
+    );
+}
+
+#[test]
+fn test_empty_nameables_macro() {
+    #[derive(Debug)]
+    struct Config;
+    empty_nameables!(Config);
+
+    let config = (Num(70707070).with_name("cfg"), Config);
+    assert_eq!(config.nameables().len(), 1);
+    assert_eq!(format!("{:?}", config.renamed()), "(⟪ID|cfg⟫, Config)");
+}
+
+#[test]
+fn test_non_ascii_short() {
+    #[derive(Debug, derive_more::Display)]
+    struct Word(&'static str);
+
+    impl Nameable for Word {
+        fn shortener(&self) -> Option<Shortener> {
+            Some(Shortener {
+                length: 3,
+                prefix: "W",
+            })
+        }
+    }
+
+    let named = Word("é€abc").with_name("word");
+    let short = Word("ü€xyz").with_short();
+    assert_eq!(short.short(), "ü€x");
+    assert_eq!(
+        format!("{:?}", (named, short).renamed()),
+        "(⟪W|word⟫, ⟪W|ü€x⟫)"
+    );
+}
+
+#[test]
+fn test_borrowed_leaves() {
+    use std::{ffi::OsStr, path::Path};
+
+    #[derive(Debug, RenameAll)]
+    struct Args<'a> {
+        id: Num,
+        path: &'a Path,
+        arg: &'a OsStr,
+        words: Vec<&'a str>,
+        paths: Vec<&'a Path>,
+    }
+
+    let args = Args {
+        id: Num(79797979).with_name("args"),
+        path: Path::new("/tmp"),
+        arg: OsStr::new("-v"),
+        words: vec!["a", "b"],
+        paths: vec![Path::new("/etc")],
+    };
+    assert_eq!(args.nameables().len(), 1);
+    assert_eq!(
+        format!("{:?}", args.renamed()),
+        r#"Args { id: ⟪ID|args⟫, path: "/tmp", arg: "-v", words: ["a", "b"], paths: ["/etc"] }"#
+    );
+}
+
+#[test]
+fn test_std_leaves() {
+    use std::{net::SocketAddr, num::NonZeroU32, path::PathBuf, time::Duration};
+
+    #[derive(Debug, Clone, RenameAll)]
+    struct Peer {
+        id: Num,
+        name: String,
+        data_dir: PathBuf,
+        addr: SocketAddr,
+        timeout: Duration,
+        retries: NonZeroU32,
+    }
+
+    let peer = Peer {
+        id: Num(13131313).with_name("peer"),
+        name: "alice".to_string(),
+        data_dir: PathBuf::from("/tmp/peer"),
+        addr: "127.0.0.1:8080".parse().unwrap(),
+        timeout: Duration::from_secs(1),
+        retries: NonZeroU32::new(3).unwrap(),
+    };
+    assert_eq!(peer.nameables().len(), 1);
+    assert_eq!(
+        format!("{:?}", peer.renamed()),
+        r#"Peer { id: ⟪ID|peer⟫, name: "alice", data_dir: "/tmp/peer", addr: 127.0.0.1:8080, timeout: 1s, retries: 3 }"#
+    );
+}
+
+#[test]
+fn test_slices_and_trait_objects() {
+    #[derive(Debug, Clone, RenameAll)]
+    struct View<'a> {
+        ids: &'a [Num],
+        label: &'a str,
+    }
+
+    let ids = [Num::sh(14141414), Num::sh(15151515)];
+    let view = View {
+        ids: &ids[..],
+        label: "view",
+    };
+    assert_eq!(
+        format!("{:?}", view.renamed()),
+        r#"View { ids: [⟪ID|1414⟫, ⟪ID|1515⟫], label: "view" }"#
+    );
+    assert_eq!(ids[..].nameables().len(), 2);
+
+    let things: Vec<Box<dyn Rename>> = vec![
+        Box::new(Num::sh(16161616)),
+        Box::new(vec![Hex::sh(17)]),
+        Box::new(Some((Num::sh(18181818), 1u8))),
+    ];
+    assert_eq!(
+        format!("{:?}", things.renamed()),
+        "[⟪ID|1616⟫, [⟪X|1111⟫], Some((⟪ID|1818⟫, 1))]"
+    );
+}
+
+#[test]
+fn test_large_tuples() {
+    #[derive(Debug, Clone, RenameAll)]
+    #[allow(unused)]
+    struct Pair(Num, #[named_id(skip)] Num, Hex);
+
+    let t = (
+        Num::sh(19191919),
+        1u8,
+        2u16,
+        3u32,
+        4u64,
+        5u128,
+        6usize,
+        7i8,
+        8i16,
+        9i32,
+        (Num::sh(20202020),),
+        Pair(Num::sh(21212121), Num(24242424), Hex::sh(23)),
+    );
+    assert_eq!(t.nameables().len(), 4);
+    assert_eq!(
+        format!("{:?}", t.renamed()),
+        "(⟪ID|1919⟫, 1, 2, 3, 4, 5, 6, 7, 8, 9, (⟪ID|2020⟫,), Pair(⟪ID|2121⟫, Num(24242424), ⟪X|1717⟫))"
+    );
+}
+
+#[test]
+fn test_ready_made_ids() {
+    id_spec!(Agent, "AG");
+    id_spec!(Key, "K", 6);
+
+    type AgentId = HexId<32, Agent>;
+    type KeyId = Base64Id<5, Key>;
+
+    let agent = AgentId::new([0xab; 32]).with_name("alice");
+    let key = KeyId::new([0xfb, 0xff, 0x01, 0x02, 0x03]).with_short();
+    assert_eq!(key.to_string(), "-_8BAgM");
+    assert_eq!(format!("{key:?}"), "K(-_8BAgM)");
+    assert_eq!(
+        format!("{:?}", (agent, key, AgentId::new([0xcd; 32])).renamed()),
+        "(⟪AG|alice⟫, ⟪K|-_8BAg⟫, AG(cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd))"
+    );
+}
+
+#[test]
+fn test_no_clone() {
+    #[derive(Debug, derive_more::Display)]
+    struct Unique(u64);
+
+    impl Nameable for Unique {
+        fn shortener(&self) -> Option<Shortener> {
+            Some(Shortener {
+                length: 4,
+                prefix: "U",
+            })
+        }
+    }
+
+    #[derive(Debug, RenameAll)]
+    struct Holder {
+        ids: Vec<Unique>,
+    }
+
+    let mut holder = Holder {
+        ids: vec![Unique(41414141).with_short(), Unique(42424242).with_short()],
+    };
+    assert_eq!(
+        format!("{:?}", holder.renamed_ref()),
+        "Holder { ids: [⟪U|4141⟫, ⟪U|4242⟫] }"
+    );
+    assert_eq!(
+        format!("{:?}", Renamed::from_ref(&holder.ids[1..])),
+        "[⟪U|4242⟫]"
+    );
+    assert_eq!(format!("{:?}", (&mut holder.ids[0]).renamed()), "⟪U|4141⟫");
+}
+
+#[test]
+fn test_manual_visitor() {
+    #[derive(Debug)]
+    struct Pair(Num, Vec<Hex>);
+
+    impl Rename for Pair {
+        fn visit_nameables<'a>(&'a self, visitor: &mut dyn FnMut(AnyNameable<'a>)) {
+            self.0.visit_nameables(visitor);
+            self.1.visit_nameables(visitor);
+        }
+    }
+
+    let pair = Pair(Num::sh(51515151), vec![Hex::sh(52)]);
+    let nameables = pair.nameables();
+    assert_eq!(nameables.len(), 2);
+    assert_eq!(format!("{:?}", nameables[0]), "Num(51515151)");
+    // Nameables are borrowed from the value rather than copied
+    assert!(std::ptr::addr_eq(&*nameables[0], &pair.0));
+    assert_eq!(
+        format!("{:?}", pair.renamed_ref()),
+        "Pair(⟪ID|5151⟫, [⟪X|3434⟫])"
+    );
+}
+
+#[test]
+fn test_unique_nameables() {
+    let s = std::collections::BTreeMap::from([
+        (
+            Num::sh(61616161),
+            vec![Num::sh(62626262), Num::sh(61616161)],
+        ),
+        (Num::sh(62626262), vec![Num::sh(61616161)]),
+    ]);
+    assert_eq!(s.nameables().len(), 5);
+    let unique = s.unique_nameables();
+    assert_eq!(
+        unique.iter().map(|n| format!("{n:?}")).collect::<Vec<_>>(),
+        ["Num(61616161)", "Num(62626262)"]
+    );
+    assert_eq!(
+        format!("{:?}", s.renamed_ref()),
+        "{⟪ID|6161⟫: [⟪ID|6262⟫, ⟪ID|6161⟫], ⟪ID|6262⟫: [⟪ID|6161⟫]}"
+    );
+}
+
+#[test]
+fn test_default_name_kind_per_type() {
+    #[derive(Debug, derive_more::Display)]
+    struct Auto(u64);
+
+    impl Nameable for Auto {
+        fn shortener(&self) -> Option<Shortener> {
+            Some(Shortener {
+                length: 4,
+                prefix: "A",
+            })
+        }
+
+        fn default_name_kind(&self) -> Option<DefaultNameKind> {
+            Some(DefaultNameKind::Short)
+        }
+    }
+
+    let v = vec![Auto(71717171), Auto(72727272).with_name("seven")];
+    assert_eq!(
+        format!("{:?}", (v.as_slice(), Num(73737373)).renamed()),
+        "([⟪A|7171⟫, ⟪A|seven⟫], Num(73737373))"
+    );
+    // Deref'd and boxed values share the type's policy.
+    assert_eq!(
+        format!("{:?}", Box::new(Auto(74747474)).renamed()),
+        "⟪A|7474⟫"
+    );
+}
+
+#[test]
+fn test_with_attr() {
+    #[derive(Debug)]
+    struct Registry {
+        ids: Vec<Num>,
+    }
+
+    fn registry_nameables(registry: &std::sync::Mutex<Registry>) -> Vec<AnyNameable<'_>> {
+        let registry = registry.lock().unwrap();
+        registry
+            .ids
+            .iter()
+            .map(|id| AnyNameable::new(id.clone()))
+            .collect()
+    }
+
+    #[derive(Debug, RenameAll)]
+    struct Service {
+        #[named_id(with = registry_nameables)]
+        registry: std::sync::Mutex<Registry>,
+        owner: Hex,
+    }
+
+    #[derive(Debug, RenameAll)]
+    enum Event {
+        Registered(#[named_id(with = registry_nameables)] std::sync::Mutex<Registry>),
+    }
+
+    let service = Service {
+        registry: std::sync::Mutex::new(Registry {
+            ids: vec![Num::sh(91919191)],
+        }),
+        owner: Hex::sh(92),
+    };
+    assert_eq!(
+        format!("{:?}", service.renamed_ref()),
+        "Service { registry: Mutex { data: Registry { ids: [⟪ID|9191⟫] }, poisoned: false, .. }, owner: ⟪X|5c5c⟫ }"
+    );
+
+    let event = Event::Registered(std::sync::Mutex::new(Registry {
+        ids: vec![Num::sh(93939393)],
+    }));
+    assert_eq!(event.nameables().len(), 1);
+}
+
+#[test]
+fn test_bound_attr() {
+    trait Entity {
+        type Id: Nameable;
+    }
+
+    #[derive(Debug)]
+    struct User;
+
+    impl Entity for User {
+        type Id = Num;
+    }
+
+    #[derive(RenameAll)]
+    #[named_id(bound = "E::Id: Rename")]
+    struct Ref<E: Entity> {
+        id: E::Id,
+        entity: std::marker::PhantomData<fn(E)>,
+    }
+
+    impl<E: Entity> std::fmt::Debug for Ref<E> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("Ref").field("id", &self.id).finish()
+        }
+    }
+
+    #[derive(RenameNone)]
+    #[named_id(bound = "")]
+    struct Marker<T>(std::marker::PhantomData<fn(T)>);
+
+    impl<T> std::fmt::Debug for Marker<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("Marker")
+        }
+    }
+
+    struct NotDebug;
+
+    let r = Ref::<User> {
+        id: Num::sh(10101010),
+        entity: std::marker::PhantomData,
+    };
+    assert_eq!(format!("{:?}", r.renamed()), "Ref { id: ⟪ID|1010⟫ }");
+    assert_eq!(
+        Marker::<NotDebug>(std::marker::PhantomData)
+            .nameables()
+            .len(),
+        0
+    );
+}
+
+mod facade {
+    pub mod reexports {
+        pub use named_id;
+    }
+}
+
+mod without_imports {
+    use super::common::{Hex, Num};
+
+    #[derive(Debug, named_id::RenameAll)]
+    #[named_id(crate = "crate::facade::reexports::named_id")]
+    pub struct Pair<T>(pub Num, pub Vec<T>);
+
+    #[derive(Debug, named_id::RenameNone)]
+    #[named_id(crate = "crate::facade::reexports::named_id")]
+    pub struct Nothing;
+
+    #[derive(Debug, named_id::RenameAll)]
+    pub struct Boxed(pub Box<Hex>);
+}
+
+#[test]
+fn test_crate_attr() {
+    let pair = without_imports::Pair(Num::sh(20202020), vec![Hex::sh(21)]);
+    assert_eq!(
+        format!("{:?}", pair.renamed()),
+        "Pair(⟪ID|2020⟫, [⟪X|1515⟫])"
+    );
+    assert_eq!(without_imports::Nothing.nameables().len(), 0);
+    assert_eq!(
+        without_imports::Boxed(Box::new(Hex::sh(22)))
+            .nameables()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_only_mode() {
+    #[allow(unused)]
+    #[derive(Debug, RenameAll)]
+    #[named_id(only)]
+    struct Big {
+        #[named_id(include)]
+        id: Num,
+        count: u32,
+        #[named_id(include)]
+        parent: Option<Hex>,
+        label: &'static str,
+    }
+
+    #[derive(Debug, RenameAll)]
+    #[named_id(only)]
+    enum Change {
+        Moved(#[named_id(include)] Num, Num),
+    }
+
+    let big = Big {
+        id: Num::sh(23232323),
+        count: 3,
+        parent: Some(Hex::sh(24)),
+        label: "big",
+    };
+    assert_eq!(big.nameables().len(), 2);
+    assert_eq!(
+        format!("{:?}", big.renamed()),
+        r#"Big { id: ⟪ID|2323⟫, count: 3, parent: Some(⟪X|1818⟫), label: "big" }"#
+    );
+    assert_eq!(
+        Change::Moved(Num::sh(25252525), Num::sh(26262626))
+            .nameables()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_union() {
+    #[derive(RenameAll)]
+    #[named_id(unsafe(active = Slot::active))]
+    union Slot {
+        id: std::mem::ManuallyDrop<Num>,
+        raw: u64,
+    }
+
+    impl Slot {
+        fn active(&self) -> Option<&'static str> {
+            // The tag is kept outside the union in real code; here only `id` is ever stored.
+            Some("id")
+        }
+    }
+
+    impl std::fmt::Debug for Slot {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            // SAFETY: only `id` is ever stored
+            unsafe { f.debug_tuple("Slot").field(&*self.id).finish() }
+        }
+    }
+
+    let slot = Slot {
+        id: std::mem::ManuallyDrop::new(Num::sh(27272727)),
+    };
+    assert_eq!(format!("{:?}", slot.renamed_ref()), "Slot(⟪ID|2727⟫)");
+}
+
+#[test]
+fn test_renamed_debug() {
+    #[allow(unused)]
+    #[derive(Debug, RenameAll)]
+    struct Plain {
+        id: Num,
+        peers: Vec<(Hex, u8)>,
+        #[named_id(skip)]
+        raw: Num,
+    }
+
+    #[derive(RenamedDebug)]
+    struct Native {
+        id: Num,
+        peers: Vec<(Hex, u8)>,
+        #[named_id(skip)]
+        raw: Num,
+    }
+
+    #[derive(RenamedDebug)]
+    enum Event {
+        Joined {
+            id: Num,
+            boxed: Box<Hex>,
+        },
+        Left(Num),
+        #[named_id(skip)]
+        Raw(Num),
+        Reset,
+    }
+
+    let plain = Plain {
+        id: Num::sh(28282828),
+        peers: vec![(Hex::sh(29), 1)],
+        raw: Num::sh(30303030),
+    };
+    let native = Native {
+        id: Num::sh(28282828),
+        peers: vec![(Hex::sh(29), 1)],
+        raw: Num::sh(30303030),
+    };
+    // The same output as `Renamed`, but for the type name
+    assert_eq!(
+        format!("{native:?}"),
+        format!("{:?}", plain.renamed_ref()).replace("Plain", "Native")
+    );
+    assert_eq!(
+        format!("{native:#?}"),
+        format!("{:#?}", plain.renamed_ref()).replace("Plain", "Native")
+    );
+    assert_eq!(
+        format!("{native:?}"),
+        "Native { id: ⟪ID|2828⟫, peers: [(⟪X|1d1d⟫, 1)], raw: Num(30303030) }"
+    );
+
+    assert_eq!(
+        format!(
+            "{:?}",
+            [
+                Event::Joined {
+                    id: Num::sh(28282828),
+                    boxed: Box::new(Hex::sh(29)),
+                },
+                Event::Left(Num::sh(28282828)),
+                Event::Raw(Num::sh(28282828)),
+                Event::Reset,
+            ]
+        ),
+        "[Joined { id: ⟪ID|2828⟫, boxed: ⟪X|1d1d⟫ }, Left(⟪ID|2828⟫), Raw(Num(28282828)), Reset]"
+    );
+}
+
+#[test]
+fn test_name_field() {
+    #[derive(Debug, derive_more::Display, RenameAll)]
+    enum Op {
+        #[named_id(name_field = "0")]
+        #[display("create {_0}")]
+        Create(Num, u8),
+        #[named_id(name_field = "id")]
+        #[display("delete {id}")]
+        Delete { force: bool, id: Num },
+        #[display("clear")]
+        Clear,
+        #[display("rename {_0} to {_1}")]
+        Rename(Num, Num),
+    }
+
+    let create = Op::Create(Num(34343434), 1).with_short();
+    let delete = Op::Delete {
+        force: true,
+        id: Num(35353535),
+    }
+    .with_name("cleanup");
+    assert_eq!(create.short(), "3434");
+    assert_eq!(
+        format!("{:?}", vec![create, delete, Op::Clear].renamed()),
+        "[⟪ID|3434⟫, ⟪ID|cleanup⟫, Clear]"
+    );
+
+    // Until the enum itself is named, its fields are renamed as usual
+    let alice = Num(75757575).with_name("alice");
+    let bob = Num(76767676).with_name("bob");
+    let ops = vec![
+        Op::Create(alice.clone(), 2),
+        Op::Delete {
+            force: false,
+            id: alice,
+        },
+        Op::Rename(bob, Num(78787878)),
+    ];
+    assert_eq!(
+        format!("{:?}", ops.renamed()),
+        "[Create(⟪ID|alice⟫, 2), Delete { force: false, id: ⟪ID|alice⟫ }, Rename(⟪ID|bob⟫, Num(78787878))]"
+    );
+}
+
+#[test]
+fn test_serialize_named() {
+    #[derive(
+        Debug,
+        Clone,
+        PartialEq,
+        Eq,
+        PartialOrd,
+        Ord,
+        derive_more::Display,
+        serde::Serialize,
+        serde::Deserialize,
+    )]
+    struct Key(u64);
+
+    impl Nameable for Key {
+        fn shortener(&self) -> Option<Shortener> {
+            Some(Shortener {
+                length: 4,
+                prefix: "K",
+            })
+        }
+    }
+
+    #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    struct Entry {
+        #[serde(with = "named_id::serde_names")]
+        key: Key,
+        #[serde(with = "named_id::serde_names")]
+        unnamed: Key,
+    }
+
+    let entry = Entry {
+        key: Key(36363636).with_short(),
+        unnamed: Key(37373737),
+    };
+    let json = serde_json::to_string(&entry).unwrap();
+    assert_eq!(json, r#"{"key":"⟪K|3636⟫","unnamed":37373737}"#);
+    assert_eq!(
+        serde_json::to_string(&[named_id::serde_names::Named(&entry.key)]).unwrap(),
+        r#"["⟪K|3636⟫"]"#
+    );
+
+    let parsed: Entry = serde_json::from_str(r#"{"key":38383838,"unnamed":1}"#).unwrap();
+    assert_eq!(parsed.key.0, 38383838);
+
+    #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    struct Index {
+        #[serde(with = "named_id::serde_names::option")]
+        head: Option<Key>,
+        #[serde(with = "named_id::serde_names::seq")]
+        keys: Vec<Key>,
+        #[serde(with = "named_id::serde_names::map_keys")]
+        counts: std::collections::BTreeMap<Key, u32>,
+        entry: Entry,
+    }
+
+    let index = Index {
+        head: Some(Key(36363636)),
+        keys: vec![Key(36363636), Key(39393939)],
+        counts: std::collections::BTreeMap::from([(Key(36363636), 1), (Key(39393939), 2)]),
+        entry,
+    };
+    assert_eq!(
+        serde_json::to_string(&index).unwrap(),
+        r#"{"head":"⟪K|3636⟫","keys":["⟪K|3636⟫",39393939],"counts":{"⟪K|3636⟫":1,"39393939":2},"entry":{"key":"⟪K|3636⟫","unnamed":37373737}}"#
+    );
+
+    let parsed: Index = serde_json::from_str(
+        r#"{"head":null,"keys":[1],"counts":{"2":3},"entry":{"key":4,"unnamed":5}}"#,
+    )
+    .unwrap();
+    assert!(parsed.head.is_none());
+    assert_eq!(parsed.keys[0].0, 1);
+    assert_eq!(parsed.counts[&Key(2)], 3);
+}